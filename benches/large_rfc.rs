@@ -0,0 +1,115 @@
+//! Peak memory of cleaning and reading back a multi-megabyte RFC, which is
+//! what matters on a low-RAM machine and what time-based harnesses don't
+//! measure. Run with `cargo bench --bench large_rfc`.
+
+use flate2::read::GzDecoder;
+use rfcli::{clean_rfc_text, config, for_each_clean_line, read_cached_rfc, rfc_cache_path, write_cached_rfc};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::Read;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// The system allocator, keeping count of the bytes allocated now and at most.
+struct Counting;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+fn grew(bytes: usize) {
+    let now = CURRENT.fetch_add(bytes, Ordering::Relaxed) + bytes;
+    PEAK.fetch_max(now, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            if new_size > layout.size() {
+                grew(new_size - layout.size());
+            } else {
+                CURRENT.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+            }
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// What `f` returns, the most it had allocated at once beyond what was
+/// allocated before, and how long it took.
+fn measure<T>(f: impl FnOnce() -> T) -> (T, usize, Duration) {
+    let base = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    let start = Instant::now();
+    let result = f();
+    (result, PEAK.load(Ordering::Relaxed) - base, start.elapsed())
+}
+
+fn report(name: &str, (bytes, peak, elapsed): (usize, usize, Duration)) {
+    println!("{:<26} {:>6.1} MB out  {:>6.1} MB peak  {:>9.1?}", name, mib(bytes), mib(peak), elapsed);
+}
+
+fn mib(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+/// `pages` pages of prose and a figure, with footers and running headers.
+fn large_rfc(pages: usize) -> String {
+    let mut text = String::new();
+    for page in 1..=pages {
+        text.push_str("RFC 9999                     A Large Protocol                  June 2026\n\n");
+        for line in 0..40 {
+            text.push_str(&format!("   Line {} of page {} describes how a sender frames its messages.\n", line, page));
+        }
+        text.push_str("\n   +--------+   request    +--------+\n   | client | -----------> | server |\n   +--------+              +--------+\n\n\n");
+        text.push_str(&format!("Author                       Standards Track                  [Page {}]\n\x0C\n", page));
+    }
+    text
+}
+
+fn main() {
+    let cache = std::env::temp_dir().join(format!("rfcli-bench-{}", std::process::id()));
+    config::init(config::Config { cache_dir: Some(cache.clone()), ..Default::default() });
+
+    let text = large_rfc(2500);
+    println!("{:.1} MB RFC, {} lines\n", mib(text.len()), text.lines().count());
+
+    report("clean_rfc_text", measure(|| clean_rfc_text(&text).len()));
+    report(
+        "for_each_clean_line",
+        measure(|| {
+            let mut bytes = 0;
+            for_each_clean_line(&text, |line| bytes += line.len() + 1);
+            bytes
+        }),
+    );
+
+    write_cached_rfc(9999, &text).unwrap();
+    report("read_cached_rfc", measure(|| read_cached_rfc(9999).unwrap().len()));
+    // What reading the cache cost before the buffer was sized from the gzip trailer
+    report(
+        "GzDecoder::read_to_string",
+        measure(|| {
+            let mut text = String::new();
+            GzDecoder::new(std::fs::File::open(rfc_cache_path(9999)).unwrap()).read_to_string(&mut text).unwrap();
+            text.len()
+        }),
+    );
+
+    let _ = std::fs::remove_dir_all(&cache);
+}
@@ -0,0 +1,134 @@
+use crate::sections;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// "   request-line   = method SP request-target SP HTTP-version CRLF", also "=/" alternatives.
+static RULE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\s*)[A-Za-z][A-Za-z0-9-]*\s*=/?\s*\S").unwrap());
+
+/// Best-effort extraction of the ABNF rules in a (cleaned) RFC text. A rule
+/// is a `name = ...` line plus every following line indented deeper than
+/// the name. When the RFC has a "Collected ABNF" section, only that one is
+/// used, it repeats every rule of the document. Rules come back dedented,
+/// with a blank line wherever prose separated them in the text.
+pub fn extract(text: &str) -> String {
+    let collected = sections::parse_sections(text)
+        .into_iter()
+        .find(|section| section.title.to_lowercase().contains("collected abnf"));
+    let text = match &collected {
+        Some(section) => sections::section_text(text, section),
+        None => text.to_string(),
+    };
+
+    let lines: Vec<&str> = text.lines().collect();
+    // (indent of the rule name, lines of the rule), None marks a gap between groups
+    let mut rules: Vec<Option<(usize, Vec<&str>)>> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(caps) = RULE_RE.captures(lines[i]) else {
+            if rules.last().is_some_and(Option::is_some) && !lines[i].trim().is_empty() {
+                rules.push(None);
+            }
+            i += 1;
+            continue;
+        };
+        let indent = caps[1].len();
+        let mut rule = vec![lines[i]];
+        i += 1;
+        // Continuations are indented past the rule name, the next rule isn't
+        while i < lines.len() && !lines[i].trim().is_empty() && indent_of(lines[i]) > indent {
+            rule.push(lines[i]);
+            i += 1;
+        }
+        rules.push(Some((indent, rule)));
+    }
+
+    let mut out = String::new();
+    let mut seen: Vec<String> = Vec::new();
+    for rule in rules {
+        match rule {
+            Some((indent, rule)) => {
+                let rule: String = rule.iter().map(|line| format!("{}\n", line.get(indent..).unwrap_or(line.trim_start()).trim_end())).collect();
+                // Without a collected section, rules quoted twice in the text print once
+                if !seen.contains(&rule) {
+                    out.push_str(&rule);
+                    seen.push(rule);
+                }
+            }
+            None if !out.is_empty() && !out.ends_with("\n\n") => out.push('\n'),
+            None => {}
+        }
+    }
+    if out.trim().is_empty() {
+        String::new()
+    } else {
+        out.trim_end().to_string() + "\n"
+    }
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uses_only_the_collected_abnf_section() {
+        let text = "2.  Message Format
+
+   A message starts with its start line:
+
+     start-line     = request-line / status-line
+
+Appendix B.  Collected ABNF
+
+   HTTP-message   = start-line *( header-field CRLF ) CRLF
+                    [ message-body ]
+   start-line     = request-line / status-line
+   status-line    = HTTP-version SP status-code SP reason-phrase CRLF
+
+Appendix C.  Acknowledgments
+
+   token = \"not a rule of this RFC\"
+";
+        assert_eq!(
+            extract(text),
+            "HTTP-message   = start-line *( header-field CRLF ) CRLF
+                 [ message-body ]
+start-line     = request-line / status-line
+status-line    = HTTP-version SP status-code SP reason-phrase CRLF
+"
+        );
+    }
+
+    #[test]
+    fn collects_rules_from_the_text_once_each() {
+        let text = "   The URI syntax:
+
+     URI         = scheme \":\" hier-part
+     scheme      = ALPHA *( ALPHA / DIGIT / \"+\" / \"-\" / \".\" )
+
+   Both are repeated further down:
+
+     URI         = scheme \":\" hier-part
+     hier-part   = \"//\" authority path-abempty
+                 =/ path-absolute
+";
+        assert_eq!(
+            extract(text),
+            "URI         = scheme \":\" hier-part
+scheme      = ALPHA *( ALPHA / DIGIT / \"+\" / \"-\" / \".\" )
+
+hier-part   = \"//\" authority path-abempty
+            =/ path-absolute
+"
+        );
+    }
+
+    #[test]
+    fn prose_has_no_rules() {
+        assert_eq!(extract("   A sentence, not a rule.\n\n   Neither is this one = or that.\n"), "");
+    }
+}
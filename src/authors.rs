@@ -0,0 +1,122 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// "Authors' Addresses", "Author's Address", "Editors' Addresses", ...
+static SECTION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(?:authors?|editors?)'?s?'?(?: and \w+'?s?)? address(?:es)?\s*$").unwrap());
+/// "EMail: fielding@gbiv.com", "Phone: +1 ...", "URI:   http://..."
+static FIELD_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^([A-Za-z-]+):\s*(.*)$").unwrap());
+
+/// One entry of an RFC's "Authors' Addresses" section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Author {
+    /// "Roy T. Fielding (editor)"
+    pub name: String,
+    /// The line under the name, unless that is already the street address
+    pub affiliation: Option<String>,
+    pub email: Option<String>,
+}
+
+/// Parse the authors' addresses at the end of a (cleaned) RFC text. Each
+/// author is a block starting with their name; older RFCs put the contact
+/// fields in a block of their own right after it.
+pub fn parse_authors(text: &str) -> Vec<Author> {
+    let mut lines = text.lines().skip_while(|line| !SECTION_RE.is_match(line.trim_end()));
+    if lines.next().is_none() {
+        return Vec::new();
+    }
+    // The section ends at the next heading in column 0
+    let body: Vec<&str> = lines
+        .take_while(|line| line.trim().is_empty() || line.starts_with(char::is_whitespace))
+        .map(str::trim)
+        .collect();
+
+    let mut authors: Vec<Author> = Vec::new();
+    for block in body.split(|line| line.is_empty()).filter(|block| !block.is_empty()) {
+        if !FIELD_RE.is_match(block[0]) {
+            let affiliation = block
+                .get(1)
+                .filter(|line| !FIELD_RE.is_match(line) && !line.starts_with(|c: char| c.is_ascii_digit()))
+                .map(|line| line.to_string());
+            authors.push(Author { name: block[0].to_string(), affiliation, email: None });
+        }
+        let email = block.iter().filter_map(|line| FIELD_RE.captures(line)).find_map(|caps| {
+            caps[1].eq_ignore_ascii_case("email").then(|| caps[2].trim().to_string())
+        });
+        if let (Some(author), Some(email)) = (authors.last_mut(), email) {
+            author.email.get_or_insert(email);
+        }
+    }
+    authors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_old_address_layout() {
+        // RFC 2616: the contact fields follow each address as a block of their own
+        let text = "   ...the end of the last section.
+
+Authors' Addresses
+
+   Roy T. Fielding
+   Department of Information and Computer Science
+   University of California, Irvine
+   Irvine, CA 92697-3425
+
+   Fax: +1(949)824-1715
+   EMail: fielding@ics.uci.edu
+
+   Jon Postel
+   4676 Admiralty Way
+   Marina del Rey, CA 90292
+
+   Phone: +1 310 822 1511
+
+   Tim Berners-Lee
+   World Wide Web Consortium
+
+   EMail: timbl@w3.org
+
+Full Copyright Statement
+
+   Copyright (C) The Internet Society (1999).
+";
+        let author = |name: &str, affiliation: Option<&str>, email: Option<&str>| Author {
+            name: name.to_string(),
+            affiliation: affiliation.map(String::from),
+            email: email.map(String::from),
+        };
+        assert_eq!(
+            parse_authors(text),
+            [
+                author("Roy T. Fielding", Some("Department of Information and Computer Science"), Some("fielding@ics.uci.edu")),
+                author("Jon Postel", None, None),
+                author("Tim Berners-Lee", Some("World Wide Web Consortium"), Some("timbl@w3.org")),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_a_single_authors_address() {
+        let text = "Author's Address
+
+   Mark Nottingham
+   Prahran VIC
+   Australia
+   Email: mnot@mnot.net
+   URI:   https://www.mnot.net/
+";
+        let authors = parse_authors(text);
+        assert_eq!(authors.len(), 1);
+        assert_eq!(authors[0].name, "Mark Nottingham");
+        assert_eq!(authors[0].email.as_deref(), Some("mnot@mnot.net"));
+    }
+
+    #[test]
+    fn no_address_section_no_authors() {
+        assert!(parse_authors("1.  Introduction\n\n   The address of a host.\n").is_empty());
+    }
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// A saved RFC. The title is kept so the list reads fine without the index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub number: u32,
+    #[serde(default)]
+    pub title: String,
+}
+
+/// All bookmarks, empty when the file is missing or unreadable.
+pub fn load(path: &Path) -> Vec<Bookmark> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(path: &Path, bookmarks: &[Bookmark]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    rfcli::write_atomic(path, serde_json::to_string_pretty(bookmarks)?)
+}
@@ -0,0 +1,98 @@
+use crate::provider::Provider;
+use colored::Colorize;
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Settings read from `config.toml`. Every field is optional, CLI flags win
+/// over these and these win over the built-in defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub default_model: Option<String>,
+    pub pager: Option<String>,
+    pub cache_dir: Option<PathBuf>,
+    pub groq_api_key: Option<String>,
+    pub provider: Option<Provider>,
+    pub base_url: Option<String>,
+    /// Environment variable to read the API key from (default depends on provider)
+    pub api_key_env: Option<String>,
+    /// API key for non-Groq providers
+    pub api_key: Option<String>,
+    /// System prompt for summaries, replacing the built-in one
+    pub tldr_system_prompt: Option<String>,
+    /// Regexes for the model's conversational lines ("Here is a summary...") that
+    /// summaries leave out, each matched against a whole line ignoring case.
+    /// Replaces the built-in list, `[]` keeps every line.
+    pub tldr_filler: Option<Vec<String>>,
+    /// Price of a million prompt tokens, for the `tldr --usage` cost estimate
+    pub prompt_price_per_million: Option<f64>,
+    /// Price of a million completion tokens, for the `tldr --usage` cost estimate
+    pub completion_price_per_million: Option<f64>,
+    /// Connect/read timeout for downloads, in seconds
+    pub timeout_secs: Option<u64>,
+    /// How many times a failed download is retried
+    pub retries: Option<u32>,
+    /// Re-download the RFC index once it is older than this many days
+    pub index_ttl_days: Option<u64>,
+    /// User-Agent header sent with every request
+    pub user_agent: Option<String>,
+    /// Proxy for all requests, e.g. `http://proxy.corp:3128` (overrides HTTP(S)_PROXY)
+    pub proxy: Option<String>,
+    /// Base URLs serving `rfc-index.txt` and `rfcN.txt` like rfc-editor.org/rfc does,
+    /// tried in order before rfc-editor.org itself
+    pub mirrors: Vec<String>,
+}
+
+impl Config {
+    /// Load the config file, falling back to defaults when it is missing or broken.
+    pub fn load() -> Config {
+        let Some(path) = config_path() else { return Config::default() };
+        let Ok(raw) = fs::read_to_string(&path) else { return Config::default() };
+
+        match toml::from_str::<Config>(&raw) {
+            Ok(mut config) => {
+                config.cache_dir = config.cache_dir.map(expand_tilde);
+                config
+            }
+            Err(e) => {
+                notice!("{}: ignoring {}: {}", "Warning".yellow(), path.display(), e);
+                Config::default()
+            }
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/rfcli/config.toml`, falling back to `~/.config/rfcli/config.toml`.
+pub fn config_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("config.toml"))
+}
+
+/// `$XDG_CONFIG_HOME/rfcli`, falling back to `~/.config/rfcli`.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")))?;
+    Some(base.join("rfcli"))
+}
+
+/// Install the config for the rest of the process. Only the first call counts.
+pub fn init(config: Config) {
+    let _ = CONFIG.set(config);
+}
+
+/// The active config (defaults if `init` was never called).
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+fn expand_tilde(path: PathBuf) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path,
+    }
+}
@@ -0,0 +1,116 @@
+use colored::Colorize;
+use similar::{ChangeTag, DiffOp, TextDiff};
+
+/// A colored unified diff of two (cleaned) texts, `context` lines around each
+/// change. Empty when the texts are the same.
+pub fn unified(old: &str, new: &str, old_name: &str, new_name: &str, context: usize) -> String {
+    let diff = TextDiff::from_lines(old, new);
+    let groups = diff.grouped_ops(context);
+    if groups.is_empty() {
+        return String::new();
+    }
+
+    let mut out = format!("{}\n{}\n", format!("--- {}", old_name).bold(), format!("+++ {}", new_name).bold());
+    for group in &groups {
+        out.push_str(&format!("{}\n", hunk_header(group).cyan()));
+        for op in group {
+            for change in diff.iter_changes(op) {
+                let line = change.value().trim_end_matches('\n');
+                let line = match change.tag() {
+                    ChangeTag::Equal => format!(" {}", line).normal(),
+                    ChangeTag::Delete => format!("-{}", line).red(),
+                    ChangeTag::Insert => format!("+{}", line).green(),
+                };
+                out.push_str(&format!("{}\n", line));
+            }
+        }
+    }
+    out
+}
+
+/// The two texts in columns of `width` characters each, changed lines marked
+/// `|` (changed), `<` (only on the left) or `>` (only on the right) between them.
+pub fn side_by_side(old: &str, new: &str, old_name: &str, new_name: &str, width: usize, context: usize) -> String {
+    let diff = TextDiff::from_lines(old, new);
+    let groups = diff.grouped_ops(context);
+    if groups.is_empty() {
+        return String::new();
+    }
+
+    let mut out = format!("{} {}\n", column(old_name, width).bold(), format!("  {}", new_name).bold());
+    for group in &groups {
+        out.push_str(&format!("{}\n", hunk_header(group).cyan()));
+        for op in group {
+            let (mut left, mut right) = (Vec::new(), Vec::new());
+            for change in diff.iter_changes(op) {
+                let line = change.value().trim_end_matches('\n');
+                match change.tag() {
+                    ChangeTag::Equal => {
+                        out.push_str(&format!("{}   {}\n", column(line, width), column(line, width).trim_end()));
+                        continue;
+                    }
+                    ChangeTag::Delete => left.push(line),
+                    ChangeTag::Insert => right.push(line),
+                }
+            }
+            // Deleted and inserted lines of one change pair up row by row
+            for row in 0..left.len().max(right.len()) {
+                let (l, r) = (left.get(row), right.get(row));
+                let marker = match (l, r) {
+                    (Some(_), Some(_)) => "|".yellow(),
+                    (Some(_), None) => "<".red(),
+                    _ => ">".green(),
+                };
+                let l = column(l.copied().unwrap_or(""), width);
+                let r = column(r.copied().unwrap_or(""), width);
+                out.push_str(&format!("{} {} {}\n", l.red(), marker, r.trim_end().green()));
+            }
+        }
+    }
+    out
+}
+
+/// `@@ -12,7 +12,9 @@` with 1-based line numbers, as diff(1) prints them.
+fn hunk_header(group: &[DiffOp]) -> String {
+    let (first, last) = (&group[0], &group[group.len() - 1]);
+    let old = first.old_range().start..last.old_range().end;
+    let new = first.new_range().start..last.new_range().end;
+    format!("@@ -{},{} +{},{} @@", old.start + 1, old.len(), new.start + 1, new.len())
+}
+
+/// `line` cut or padded to exactly `width` characters.
+fn column(line: &str, width: usize) -> String {
+    let cut: String = line.chars().take(width).collect();
+    format!("{:<width$}", cut, width = width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "one\ntwo\nthree\nfour\n";
+    const NEW: &str = "one\n2\nthree\nfour\nfive\n";
+
+    #[test]
+    fn identical_texts_have_no_diff() {
+        assert_eq!(unified(OLD, OLD, "rfc2616", "rfc9110", 3), "");
+        assert_eq!(side_by_side(OLD, OLD, "rfc2616", "rfc9110", 20, 3), "");
+    }
+
+    #[test]
+    fn unified_diff_of_one_hunk() {
+        colored::control::set_override(false);
+        assert_eq!(
+            unified(OLD, NEW, "rfc2616", "rfc9110", 1),
+            "--- rfc2616\n+++ rfc9110\n@@ -1,4 +1,5 @@\n one\n-two\n+2\n three\n four\n+five\n"
+        );
+    }
+
+    #[test]
+    fn side_by_side_marks_each_row() {
+        colored::control::set_override(false);
+        let diff = side_by_side(OLD, NEW, "old", "new", 6, 0);
+        let rows: Vec<&str> = diff.lines().collect();
+        assert_eq!(rows, ["old      new", "@@ -2,1 +2,1 @@", "two    | 2", "@@ -5,0 +5,1 @@", "       > five"]);
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One line of `history.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub number: u32,
+    #[serde(default)]
+    pub title: String,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+impl HistoryEntry {
+    pub fn now(number: u32, title: String) -> HistoryEntry {
+        HistoryEntry { number, title, timestamp: unix_now() }
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Append a read to the history file.
+pub fn record(path: &Path, entry: &HistoryEntry) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let line = serde_json::to_string(entry)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// Every RFC in the history once, most recently read first. Unparseable lines are skipped.
+pub fn recent(path: &Path) -> Vec<HistoryEntry> {
+    let Ok(raw) = fs::read_to_string(path) else { return Vec::new() };
+    let mut entries: Vec<HistoryEntry> = Vec::new();
+    for entry in raw.lines().rev().filter_map(|line| serde_json::from_str::<HistoryEntry>(line).ok()) {
+        if !entries.iter().any(|seen| seen.number == entry.number) {
+            entries.push(entry);
+        }
+    }
+    entries
+}
@@ -0,0 +1,300 @@
+use colored::Colorize;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Tags, comments and doctypes. Group 1 is "/" on end tags, 2 the tag name, 3 its attributes.
+static TAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<!--.*?-->|<![^>]*>|<(/?)([a-zA-Z][a-zA-Z0-9]*)([^>]*)>").unwrap());
+static CLASS_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"class\s*=\s*"([^"]*)""#).unwrap());
+static HREF_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"href\s*=\s*"([^"]*)""#).unwrap());
+static ENTITY_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap());
+
+/// Width prose is wrapped at, the same as the plain text RFCs.
+const WIDTH: usize = 72;
+/// Elements whose content never shows up on screen.
+const HIDDEN: &[&str] = &["head", "script", "style", "template"];
+/// Elements that start a new block of text when they open or close.
+const BLOCKS: &[&str] = &[
+    "address", "aside", "blockquote", "dd", "div", "dl", "dt", "figcaption", "figure", "footer", "h1", "h2", "h3", "h4",
+    "h5", "h6", "header", "li", "nav", "ol", "p", "pre", "section", "table", "tr", "ul",
+];
+
+/// Render the HTML edition of an RFC (as xml2rfc writes it) as styled
+/// terminal text: bold headings, underlined links, colored code and
+/// verbatim figures. Best effort, not a general HTML renderer.
+pub fn to_text(html: &str) -> String {
+    let mut renderer = Renderer::default();
+    let mut last = 0;
+    for caps in TAG_RE.captures_iter(html) {
+        let whole = caps.get(0).unwrap();
+        renderer.text(&html[last..whole.start()]);
+        last = whole.end();
+        let Some(name) = caps.get(2) else { continue }; // a comment or doctype
+        let name = name.as_str().to_ascii_lowercase();
+        if caps[1].is_empty() {
+            renderer.open(&name, &caps[3]);
+        } else {
+            renderer.close(&name);
+        }
+    }
+    renderer.text(&html[last..]);
+    renderer.flush();
+    renderer.out.trim_start_matches('\n').trim_end().to_string() + "\n"
+}
+
+#[derive(Default)]
+struct Renderer {
+    out: String,
+    /// Inline text of the block being built, styles already applied
+    line: String,
+    /// The hidden element (or skipped anchor) we're in and how deeply it
+    /// nests in itself, nothing is printed inside it
+    hidden: Option<(String, usize)>,
+    heading: bool,
+    pre: bool,
+    /// Nothing of the `pre` printed yet, its first newline only follows the tag
+    pre_start: bool,
+    code: bool,
+    bold: bool,
+    italic: bool,
+    /// Target of the link being read, shown after it when it leaves the document
+    link: Option<String>,
+    /// Nesting of ul/ol/dl, for indentation
+    lists: usize,
+    /// A list item marker waiting for its first text
+    bullet: bool,
+}
+
+impl Renderer {
+    fn open(&mut self, name: &str, attrs: &str) {
+        let class = CLASS_RE.captures(attrs).map(|caps| caps[1].to_string()).unwrap_or_default();
+        // Self-links (¶) and back-to-top arrows make sense in a browser only
+        let decoration = name == "a" && class.split_whitespace().any(|c| c == "pilcrow" || c == "toplink");
+        match &mut self.hidden {
+            Some((hidden, depth)) => {
+                if hidden == name {
+                    *depth += 1;
+                }
+                return;
+            }
+            None if HIDDEN.contains(&name) || decoration => {
+                self.hidden = Some((name.to_string(), 1));
+                return;
+            }
+            None => {}
+        }
+        if BLOCKS.contains(&name) {
+            self.flush();
+        }
+        match name {
+            "br" => self.flush(),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.out.push('\n');
+                self.heading = true;
+            }
+            // A list item's paragraph sits right on its bullet
+            "p" if self.bullet => {}
+            "p" | "dl" | "table" | "figure" | "aside" | "blockquote" => self.out.push('\n'),
+            "pre" => {
+                self.out.push('\n');
+                self.pre = true;
+                self.pre_start = true;
+            }
+            "ul" | "ol" => {
+                if self.lists == 0 {
+                    self.out.push('\n');
+                }
+                self.lists += 1;
+            }
+            "li" => self.bullet = true,
+            "dd" => self.lists += 1,
+            "code" | "tt" | "kbd" | "samp" => self.code = true,
+            "strong" | "b" => self.bold = true,
+            "em" | "i" | "var" | "cite" => self.italic = true,
+            "a" => {
+                let href = HREF_RE.captures(attrs).map(|caps| decode(&caps[1]));
+                self.link = Some(href.filter(|href| href.starts_with("http")).unwrap_or_default());
+            }
+            "td" | "th" => self.line.push_str("  "),
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        if let Some((hidden, depth)) = &mut self.hidden {
+            if hidden == name {
+                *depth -= 1;
+                if *depth == 0 {
+                    self.hidden = None;
+                }
+            }
+            return;
+        }
+        if BLOCKS.contains(&name) {
+            self.flush();
+        }
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => self.heading = false,
+            "pre" => self.pre = false,
+            "ul" | "ol" | "dd" => self.lists = self.lists.saturating_sub(1),
+            "code" | "tt" | "kbd" | "samp" => self.code = false,
+            "strong" | "b" => self.bold = false,
+            "em" | "i" | "var" | "cite" => self.italic = false,
+            "a" => {
+                if let Some(href) = self.link.take().filter(|href| !href.is_empty()) {
+                    self.line.push_str(&format!(" <{}>", href).dimmed().to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, raw: &str) {
+        if self.hidden.is_some() || raw.is_empty() {
+            return;
+        }
+        let text = decode(raw);
+        if self.pre {
+            let text = match std::mem::take(&mut self.pre_start) {
+                true => text.strip_prefix('\n').unwrap_or(&text),
+                false => &text,
+            };
+            // Figures and code keep their exact layout, one styled line at a time
+            for (i, line) in text.split('\n').enumerate() {
+                if i > 0 {
+                    self.out.push_str(&format!("   {}\n", self.line.trim_end()));
+                    self.line.clear();
+                }
+                self.line.push_str(&line.cyan().to_string());
+            }
+            return;
+        }
+
+        let text: String = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let spaced = raw.starts_with(char::is_whitespace) && !self.line.is_empty() && !self.line.ends_with(' ');
+        if spaced {
+            self.line.push(' ');
+        }
+        if text.is_empty() {
+            return;
+        }
+        if self.bullet {
+            self.line.push_str("• ");
+            self.bullet = false;
+        }
+        let mut styled = text.normal();
+        if self.heading {
+            styled = styled.bold().yellow();
+        }
+        if self.code {
+            styled = styled.cyan();
+        }
+        if self.bold {
+            styled = styled.bold();
+        }
+        if self.italic {
+            styled = styled.italic();
+        }
+        if self.link.is_some() {
+            styled = styled.underline();
+        }
+        self.line.push_str(&styled.to_string());
+        if raw.ends_with(char::is_whitespace) {
+            self.line.push(' ');
+        }
+    }
+
+    /// End the block being built: wrapped and indented, or as is in a figure.
+    fn flush(&mut self) {
+        let line = std::mem::take(&mut self.line);
+        if self.pre {
+            if !line.trim().is_empty() {
+                self.out.push_str(&format!("   {}\n", line.trim_end()));
+            }
+            return;
+        }
+        if line.trim().is_empty() {
+            return;
+        }
+        let indent = if self.heading { String::new() } else { " ".repeat(3 + 3 * self.lists) };
+        let options = textwrap::Options::new(WIDTH).initial_indent(&indent).subsequent_indent(&indent);
+        for wrapped in textwrap::wrap(line.trim(), options) {
+            self.out.push_str(&wrapped);
+            self.out.push('\n');
+        }
+    }
+}
+
+/// Replace character references: the handful of named ones RFCs use, and numeric ones.
+pub fn decode(text: &str) -> String {
+    ENTITY_RE
+        .replace_all(text, |caps: &regex::Captures| {
+            let entity = &caps[1];
+            let code = match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => entity.strip_prefix('#').and_then(|dec| dec.parse().ok()),
+            };
+            let decoded = match (code, entity) {
+                (Some(code), _) => char::from_u32(code).map(|c| if c == '\u{a0}' { ' ' } else { c }),
+                (None, "amp") => Some('&'),
+                (None, "lt") => Some('<'),
+                (None, "gt") => Some('>'),
+                (None, "quot") => Some('"'),
+                (None, "apos") => Some('\''),
+                (None, "nbsp") => Some(' '),
+                _ => None,
+            };
+            decoded.map(String::from).unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(html: &str) -> String {
+        colored::control::set_override(false);
+        to_text(html)
+    }
+
+    #[test]
+    fn renders_headings_paragraphs_and_lists() {
+        let html = r##"<!DOCTYPE html><html><head><title>RFC 9110</title><style>p { margin: 0 }</style></head><body>
+<h2 id="section-1">1. <a href="#section-1" class="pilcrow">¶</a>Introduction</h2>
+<p>The Hypertext Transfer Protocol is a <em>stateless</em> protocol.<a href="#p-1" class="pilcrow">¶</a></p>
+<ul><li><p>one</p></li><li>two, see <a href="https://www.rfc-editor.org/rfc/rfc9112">HTTP/1.1</a></li></ul>
+</body></html>"##;
+        assert_eq!(
+            render(html),
+            "1. Introduction
+
+   The Hypertext Transfer Protocol is a stateless protocol.
+
+      • one
+      • two, see HTTP/1.1 <https://www.rfc-editor.org/rfc/rfc9112>
+"
+        );
+    }
+
+    #[test]
+    fn keeps_figures_verbatim() {
+        let html = "<p>The header:</p><pre>\n+--------+\n|  Type  |\n+--------+\n</pre>";
+        assert_eq!(render(html), "   The header:\n\n   +--------+\n   |  Type  |\n   +--------+\n");
+    }
+
+    #[test]
+    fn hides_everything_inside_hidden_elements() {
+        // The nested template and the figures in it stay hidden until the outer one closes
+        let html = "<template><div><pre>\nhidden figure\n</pre><template><pre>inner</pre></template><pre>still hidden</pre></div></template>\
+                    <pre>\nshown figure\n</pre><script>if (a < b) { document.write('<pre>x</pre>') }</script><p>after</p>";
+        assert_eq!(render(html), "   shown figure\n\n   after\n");
+    }
+
+    #[test]
+    fn decodes_character_references() {
+        assert_eq!(decode("&lt;tag&gt; &amp; &quot;q&quot; &#65;&#x42;&#X43;"), "<tag> & \"q\" ABC");
+        assert_eq!(decode("a&nbsp;b&#160;c"), "a b c");
+        assert_eq!(decode("&unknown; &#xZZ; &"), "&unknown; &#xZZ; &");
+    }
+}
@@ -0,0 +1,421 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+
+static EXTRAS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\s\((?:Format|Obsoletes|Obsoleted by|Updates|Updated by|Also|Status|DOI)\b").unwrap()
+});
+static DATE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\.?\s*((?:\d{1,2} )?(?:January|February|March|April|May|June|July|August|September|October|November|December) \d{4})\.?$").unwrap()
+});
+static TITLE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(.*?)\. ((?:[A-Z][a-z]?\.[ -]?)+\p{Lu}.*)$").unwrap()
+});
+static FIELD_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\((Format|Obsoletes|Obsoleted by|Updates|Updated by|Also|Status|DOI):? ([^)]*)\)").unwrap()
+});
+static RFC_REF_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"RFC\s?(\d+)").unwrap());
+
+/// One parsed entry of `rfc-index.txt`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RfcEntry {
+    pub number: u32,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub date: String,
+    pub status: String,
+    pub obsoletes: Vec<u32>,
+    pub obsoleted_by: Vec<u32>,
+    pub updates: Vec<u32>,
+    pub updated_by: Vec<u32>,
+}
+
+impl RfcEntry {
+    /// Publication year, from the trailing year of "June 1999" style dates.
+    pub fn year(&self) -> Option<u32> {
+        self.date.split_whitespace().last()?.parse().ok()
+    }
+
+    /// Publication year and month (1-12), for ordering by date. The month is
+    /// 0 when only the year can be read.
+    pub fn published(&self) -> Option<(u32, u32)> {
+        const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+        let month = self.date.split_whitespace().find_map(|word| {
+            let word = word.to_ascii_lowercase();
+            MONTHS.iter().position(|m| word.starts_with(m)).map(|i| i as u32 + 1)
+        });
+        Some((self.year()?, month.unwrap_or(0)))
+    }
+
+    /// `RFC 2616  Hypertext Transfer Protocol -- HTTP/1.1  [DRAFT STANDARD]`
+    pub fn summary_line(&self) -> String {
+        if self.status.is_empty() {
+            format!("RFC {}  {}", self.number, self.title)
+        } else {
+            format!("RFC {}  {}  [{}]", self.number, self.title, self.status)
+        }
+    }
+}
+
+/// `rfc-index.json`: the parsed index and the fingerprint of the
+/// `rfc-index.txt` it was parsed from.
+#[derive(Serialize, Deserialize)]
+struct ParsedIndex<'a> {
+    fingerprint: Cow<'a, str>,
+    entries: Cow<'a, [RfcEntry]>,
+}
+
+/// The entries saved by `save_parsed`, as long as they were parsed from the
+/// index `fingerprint` identifies.
+pub fn load_parsed(path: &Path, fingerprint: &str) -> Option<Vec<RfcEntry>> {
+    let parsed: ParsedIndex = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+    (parsed.fingerprint == fingerprint).then(|| parsed.entries.into_owned())
+}
+
+pub fn save_parsed(path: &Path, fingerprint: &str, entries: &[RfcEntry]) -> std::io::Result<()> {
+    let parsed = ParsedIndex { fingerprint: fingerprint.into(), entries: entries.into() };
+    crate::write_atomic(path, serde_json::to_vec(&parsed)?)
+}
+
+/// Parse the index one entry at a time, so callers can use entries before
+/// the whole file is through. Stops early once `f` returns false.
+pub fn for_each_entry(data: &str, mut f: impl FnMut(RfcEntry) -> bool) {
+    for_each_unwrapped(data, |text| parse_entry(text).is_none_or(&mut f));
+}
+
+/// Entries start with their number in column 0 and wrap onto indented
+/// continuation lines; everything else is preamble. Each entry is handed
+/// to `f` joined onto a single line, until `f` returns false.
+fn for_each_unwrapped(data: &str, mut f: impl FnMut(&str) -> bool) {
+    let mut current: Option<String> = None;
+
+    for line in data.lines() {
+        let starts_entry = line.chars().next().map(|c| c.is_ascii_digit()).unwrap_or(false);
+        if starts_entry || line.trim().is_empty() {
+            if let Some(text) = current.take() {
+                if !f(&text) {
+                    return;
+                }
+            }
+            if starts_entry {
+                current = Some(line.trim().to_string());
+            }
+        } else if let Some(text) = current.as_mut() {
+            text.push(' ');
+            text.push_str(line.trim());
+        }
+    }
+    if let Some(text) = current {
+        f(&text);
+    }
+}
+
+/// Parse a single unwrapped entry, e.g.
+/// `2616 Hypertext Transfer Protocol -- HTTP/1.1. R. Fielding, ... June 1999. (Format: TXT) (Status: DRAFT STANDARD)`
+pub fn parse_entry(text: &str) -> Option<RfcEntry> {
+    let (number, body) = text.split_once(' ').unwrap_or((text, ""));
+    let number = number.parse::<u32>().ok()?;
+
+    let (description, extras) = match EXTRAS_RE.find(body) {
+        Some(m) => (&body[..m.start()], &body[m.start()..]),
+        None => (body, ""),
+    };
+    let (rest, date) = match DATE_RE.captures(description) {
+        Some(caps) => (&description[..caps.get(0).unwrap().start()], caps[1].to_string()),
+        None => (description, String::new()),
+    };
+    let (title, authors) = match TITLE_RE.captures(rest) {
+        Some(caps) => (caps[1].to_string(), split_authors(&caps[2])),
+        None => (rest.trim_end_matches('.').to_string(), Vec::new()),
+    };
+
+    let mut entry = RfcEntry { number, title, authors, date, ..Default::default() };
+    for caps in FIELD_RE.captures_iter(extras) {
+        let value = &caps[2];
+        match &caps[1] {
+            "Status" => entry.status = value.trim().to_string(),
+            "Obsoletes" => entry.obsoletes = rfc_refs(value),
+            "Obsoleted by" => entry.obsoleted_by = rfc_refs(value),
+            "Updates" => entry.updates = rfc_refs(value),
+            "Updated by" => entry.updated_by = rfc_refs(value),
+            _ => {}
+        }
+    }
+
+    Some(entry)
+}
+
+/// Look up a single RFC by number.
+pub fn find(entries: &[RfcEntry], number: u32) -> Option<&RfcEntry> {
+    entries.iter().find(|entry| entry.number == number)
+}
+
+/// The RFCs that currently replace `number`, found by following "Obsoleted by"
+/// until reaching documents that are not obsoleted themselves. Ascending, and
+/// empty when `number` is current. Cycles in the index are tolerated.
+pub fn current_replacements(entries: &[RfcEntry], number: u32) -> Vec<u32> {
+    let mut seen = vec![number];
+    let mut queue = find(entries, number).map(|e| e.obsoleted_by.clone()).unwrap_or_default();
+    let mut current = Vec::new();
+
+    while let Some(next) = queue.pop() {
+        if seen.contains(&next) {
+            continue;
+        }
+        seen.push(next);
+        match find(entries, next) {
+            Some(entry) if !entry.obsoleted_by.is_empty() => queue.extend(&entry.obsoleted_by),
+            _ => current.push(next),
+        }
+    }
+
+    current.sort_unstable();
+    current
+}
+
+/// The STD, BCP and FYI subseries, whose numbers each stand for one or more RFCs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subseries {
+    Std,
+    Bcp,
+    Fyi,
+}
+
+impl Subseries {
+    pub fn name(self) -> &'static str {
+        match self {
+            Subseries::Std => "STD",
+            Subseries::Bcp => "BCP",
+            Subseries::Fyi => "FYI",
+        }
+    }
+
+    /// The rfc-editor.org index listing this subseries, also its cache file name.
+    pub fn index_file(self) -> &'static str {
+        match self {
+            Subseries::Std => "std-index.txt",
+            Subseries::Bcp => "bcp-index.txt",
+            Subseries::Fyi => "fyi-index.txt",
+        }
+    }
+}
+
+/// "std66", "STD 66", "bcp0014" -> (Std, 66), (Bcp, 14)
+pub fn parse_subseries_id(s: &str) -> Option<(Subseries, u32)> {
+    let s = s.trim();
+    let series = match s.get(..3)?.to_ascii_uppercase().as_str() {
+        "STD" => Subseries::Std,
+        "BCP" => Subseries::Bcp,
+        "FYI" => Subseries::Fyi,
+        _ => return None,
+    };
+    let digits = s[3..].trim_start();
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((series, digits.parse().ok()?))
+}
+
+/// The RFCs a subseries index lists under `number`, e.g. BCP 14 -> [2119, 8174].
+/// Empty when the number is unknown or not assigned to any RFC yet.
+pub fn subseries_rfcs(data: &str, number: u32) -> Vec<u32> {
+    let mut entry = None;
+    for_each_unwrapped(data, |text| {
+        if text.split(' ').next().and_then(|n| n.parse::<u32>().ok()) == Some(number) {
+            entry = Some(text.to_string());
+        }
+        entry.is_none()
+    });
+    let Some(entry) = entry else {
+        return Vec::new();
+    };
+    let mut numbers = rfc_refs(&entry);
+    numbers.dedup();
+    numbers
+}
+
+/// Match an index status against a user filter. Common short names map to
+/// their full status, anything else is a case-insensitive substring match.
+pub fn status_matches(status: &str, filter: &str) -> bool {
+    let filter = filter.trim().to_ascii_uppercase();
+    let wanted = match filter.as_str() {
+        "STANDARD" | "STD" | "INTERNET" => "INTERNET STANDARD",
+        "PROPOSED" => "PROPOSED STANDARD",
+        "DRAFT" => "DRAFT STANDARD",
+        "BCP" | "BEST" => "BEST CURRENT PRACTICE",
+        "INFO" => "INFORMATIONAL",
+        "EXP" => "EXPERIMENTAL",
+        other => return !other.is_empty() && status.to_ascii_uppercase().contains(other),
+    };
+    status.eq_ignore_ascii_case(wanted)
+}
+
+/// Where a status stands, 0 for full Internet Standards up to unknown
+/// statuses last, for ordering by status.
+pub fn status_rank(status: &str) -> u8 {
+    match status.to_ascii_uppercase().as_str() {
+        "INTERNET STANDARD" => 0,
+        "DRAFT STANDARD" => 1,
+        "PROPOSED STANDARD" => 2,
+        "BEST CURRENT PRACTICE" => 3,
+        "INFORMATIONAL" => 4,
+        "EXPERIMENTAL" => 5,
+        "HISTORIC" => 6,
+        _ => 7,
+    }
+}
+
+/// Case-insensitive substring match of `name` against any of the authors.
+pub fn author_matches(authors: &[String], name: &str) -> bool {
+    let name = name.trim().to_lowercase();
+    !name.is_empty() && authors.iter().any(|author| author.to_lowercase().contains(&name))
+}
+
+/// A `--since`/`--until` filter on the publication year.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct YearRange {
+    pub since: Option<u32>,
+    pub until: Option<u32>,
+    /// Keep entries without a usable date instead of dropping them
+    pub include_undated: bool,
+}
+
+impl YearRange {
+    pub fn is_set(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+
+    pub fn matches(&self, entry: &RfcEntry) -> bool {
+        if !self.is_set() {
+            return true;
+        }
+        match entry.year() {
+            Some(year) => self.since.is_none_or(|since| year >= since) && self.until.is_none_or(|until| year <= until),
+            None => self.include_undated,
+        }
+    }
+
+    /// "2015", "2015-2020", "2015 or later", "2020 or earlier"
+    pub fn describe(&self) -> String {
+        match (self.since, self.until) {
+            (Some(since), Some(until)) if since == until => since.to_string(),
+            (Some(since), Some(until)) => format!("{}-{}", since, until),
+            (Some(since), None) => format!("{} or later", since),
+            (None, Some(until)) => format!("{} or earlier", until),
+            (None, None) => "any year".to_string(),
+        }
+    }
+}
+
+/// "R. Fielding, Ed., J. Reschke, Ed." -> ["R. Fielding, Ed.", "J. Reschke, Ed."]
+fn split_authors(raw: &str) -> Vec<String> {
+    let mut authors: Vec<String> = Vec::new();
+    for part in raw.trim().trim_end_matches('.').split(", ") {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+        match authors.last_mut() {
+            Some(last) if part.starts_with("Ed") => {
+                last.push_str(", Ed.");
+            }
+            _ => authors.push(part.to_string()),
+        }
+    }
+    authors
+}
+
+fn rfc_refs(value: &str) -> Vec<u32> {
+    RFC_REF_RE
+        .captures_iter(value)
+        .filter_map(|caps| caps[1].parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Entries as rfc-index.txt wraps them, after a bit of its preamble.
+    const SAMPLE: &str = "
+      RFC INDEX
+      -------------
+
+0004 Not Issued.
+
+2616 Hypertext Transfer Protocol -- HTTP/1.1. R. Fielding, J. Gettys, J.
+     Mogul, H. Frystyk, L. Masinter, P. Leach, T. Berners-Lee. June 1999.
+     (Format: TXT, HTML) (Obsoletes RFC2068) (Obsoleted by RFC7230,
+     RFC7231, RFC7232, RFC7233, RFC7234, RFC7235) (Updated by RFC2817,
+     RFC5785, RFC6266, RFC6585) (Status: DRAFT STANDARD) (DOI:
+     10.17487/RFC2616)
+
+5280 Internet X.509 Public Key Infrastructure Certificate and Certificate
+     Revocation List (CRL) Profile. D. Cooper, S. Santesson, S. Farrell,
+     S. Boeyen, R. Housley, W. Polk. May 2008. (Format: TXT, HTML)
+     (Obsoletes RFC3280, RFC4325, RFC4630) (Updated by RFC6818, RFC8398,
+     RFC8399) (Status: PROPOSED STANDARD) (DOI: 10.17487/RFC5280)
+
+7230 Hypertext Transfer Protocol (HTTP/1.1): Message Syntax and Routing.
+     R. Fielding, Ed., J. Reschke, Ed.. June 2014. (Format: TXT, HTML)
+     (Obsoletes RFC2145, RFC2616) (Obsoleted by RFC9110, RFC9112) (Updates
+     RFC2817, RFC2818) (Status: PROPOSED STANDARD) (DOI: 10.17487/RFC7230)
+";
+
+    fn entries() -> Vec<RfcEntry> {
+        let mut entries = Vec::new();
+        for_each_entry(SAMPLE, |entry| {
+            entries.push(entry);
+            true
+        });
+        entries
+    }
+
+    #[test]
+    fn skips_the_preamble() {
+        let numbers: Vec<u32> = entries().iter().map(|entry| entry.number).collect();
+        assert_eq!(numbers, [4, 2616, 5280, 7230]);
+    }
+
+    #[test]
+    fn joins_wrapped_titles() {
+        let entries = entries();
+        let entry = find(&entries, 5280).unwrap();
+        assert_eq!(
+            entry.title,
+            "Internet X.509 Public Key Infrastructure Certificate and Certificate Revocation List (CRL) Profile"
+        );
+        assert_eq!(entry.authors, ["D. Cooper", "S. Santesson", "S. Farrell", "S. Boeyen", "R. Housley", "W. Polk"]);
+        assert_eq!(entry.date, "May 2008");
+        assert_eq!(entry.status, "PROPOSED STANDARD");
+    }
+
+    #[test]
+    fn keeps_not_issued_entries() {
+        let entries = entries();
+        let entry = find(&entries, 4).unwrap();
+        assert_eq!(entry.title, "Not Issued");
+        assert!(entry.authors.is_empty());
+        assert!(entry.date.is_empty());
+        assert!(entry.status.is_empty());
+    }
+
+    #[test]
+    fn reads_obsoletes_and_updates_across_lines() {
+        let entries = entries();
+        let http = find(&entries, 2616).unwrap();
+        assert_eq!(http.obsoletes, [2068]);
+        assert_eq!(http.obsoleted_by, [7230, 7231, 7232, 7233, 7234, 7235]);
+        assert_eq!(http.updated_by, [2817, 5785, 6266, 6585]);
+        assert!(http.updates.is_empty());
+
+        let messaging = find(&entries, 7230).unwrap();
+        assert_eq!(messaging.authors, ["R. Fielding, Ed.", "J. Reschke, Ed."]);
+        assert_eq!(messaging.obsoletes, [2145, 2616]);
+        assert_eq!(messaging.obsoleted_by, [9110, 9112]);
+        assert_eq!(messaging.updates, [2817, 2818]);
+    }
+}
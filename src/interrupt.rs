@@ -0,0 +1,44 @@
+use crossterm::{cursor, execute, terminal};
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set while a pager or editor runs in the foreground. Ctrl-C is theirs
+/// then (less stops a search with it), not a reason for us to quit.
+static CHILD_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// From now on, Ctrl-C clears any progress bar, puts the terminal back the
+/// way the shell expects it and exits with 130, as a shell reports SIGINT.
+pub fn install() {
+    tokio::spawn(async {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            if CHILD_RUNNING.load(Ordering::SeqCst) {
+                continue;
+            }
+            restore_terminal();
+            std::process::exit(130);
+        }
+    });
+}
+
+/// Run `f`, which waits for a pager or editor, leaving Ctrl-C to the child.
+pub fn with_child<T>(f: impl FnOnce() -> T) -> T {
+    CHILD_RUNNING.store(true, Ordering::SeqCst);
+    let result = f();
+    CHILD_RUNNING.store(false, Ordering::SeqCst);
+    result
+}
+
+fn restore_terminal() {
+    rfcli::clear_progress();
+    let mut err = std::io::stderr();
+    // Only the viewer puts the terminal in raw mode, on its alternate screen
+    if terminal::is_raw_mode_enabled().unwrap_or(false) {
+        let _ = terminal::disable_raw_mode();
+        let _ = execute!(err, terminal::LeaveAlternateScreen);
+    }
+    // Colors and the cursor as they were, and the prompt on a line of its own
+    if err.is_terminal() {
+        let _ = execute!(err, cursor::Show);
+        let _ = writeln!(err, "\x1b[0m");
+    }
+}
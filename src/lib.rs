@@ -0,0 +1,1337 @@
+//! Fetching, caching and cleaning RFCs, the parsed index and TLDR
+//! summaries, for tools that want to embed them. The `rfc` binary is a CLI
+//! over this that adds the prompts, pagers and exit codes. The library is
+//! silent unless `set_report` asks for progress bars and notices on stderr.
+
+use colored::Colorize;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle, WeakProgressBar};
+use regex::Regex;
+use std::borrow::Cow;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+/// `eprintln!`, when `set_report` turned reporting on.
+macro_rules! notice {
+    ($($arg:tt)*) => {
+        if $crate::reporting() {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+pub mod abnf;
+pub mod authors;
+pub mod config;
+pub mod diff;
+pub mod html;
+pub mod index;
+pub mod markdown;
+pub mod metadata;
+pub mod net;
+pub mod provider;
+pub mod sections;
+#[cfg(feature = "sqlite")]
+pub mod store;
+
+use provider::{Endpoint, Reply, Usage};
+
+static REPORT: AtomicBool = AtomicBool::new(false);
+
+/// Report on stderr what the library is doing: download progress, retries,
+/// index refreshes and the warnings about a cache it couldn't write. Off by
+/// default, so an embedding program's stderr stays its own.
+pub fn set_report(on: bool) {
+    REPORT.store(on, Ordering::Relaxed);
+}
+
+/// Whether `set_report` turned reporting on.
+pub fn reporting() -> bool {
+    REPORT.load(Ordering::Relaxed)
+}
+
+/// Why fetching an RFC, an index file or its metadata failed, or why an
+/// endpoint can't be used for summaries.
+#[derive(Debug)]
+pub enum Error {
+    /// The RFC doesn't exist, was never issued or isn't on the server
+    NotFound(String),
+    /// Offline, and it isn't in the cache
+    NotCached(String),
+    /// The download failed or brought back something unusable
+    Download(String),
+    /// The cache couldn't be read or written
+    Cache(String),
+    /// The flags and config file don't add up to a usable endpoint
+    Config(String),
+    /// Summaries from `provider` need an API key and none is configured
+    NoApiKey { provider: provider::Provider, key_env: String },
+    /// A request couldn't be sent, or the answer didn't arrive in time
+    Http(String),
+    /// The summary provider refused a request or answered with something unusable
+    Api(String),
+    /// `provider` doesn't serve `model`, `known` are the models it does
+    UnknownModel { provider: provider::Provider, model: String, known: Vec<String> },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::NotFound(message)
+            | Error::NotCached(message)
+            | Error::Download(message)
+            | Error::Cache(message)
+            | Error::Config(message)
+            | Error::Http(message)
+            | Error::Api(message) => f.write_str(message),
+            Error::NoApiKey { provider, .. } => write!(f, "no {} API key found.", provider.name()),
+            Error::UnknownModel { provider, model, .. } => {
+                write!(f, "unknown model '{}', {} doesn't serve it (`rfc models` lists the ones it does)", model, provider.name())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// `--cache-dir`, then `RFCLI_CACHE_DIR`, then `cache_dir` from the config, then the platform default.
+pub fn cache_dir() -> PathBuf {
+    if let Some(dir) = &config::get().cache_dir {
+        return dir.clone();
+    }
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("rfcli")
+}
+
+/// Cached RFC texts are gzipped, plain text compresses roughly 4:1.
+pub fn rfc_cache_path(number: u32) -> PathBuf {
+    cache_dir().join(format!("rfc{}.txt.gz", number))
+}
+
+/// The cached text of an RFC, as downloaded.
+pub fn read_cached_rfc(number: u32) -> std::io::Result<String> {
+    let mut file = fs::File::open(rfc_cache_path(number))?;
+    // Sized up front, growing by doubling would briefly need up to twice the text
+    let mut text = String::with_capacity(gzip_text_size(&mut file).unwrap_or(0));
+    GzDecoder::new(file).read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// The uncompressed size a gzip file records in its last four bytes (modulo
+/// 4 GiB, plenty for an RFC). None when it doesn't look plausible for the
+/// file's size, the caller just grows its buffer then.
+fn gzip_text_size(file: &mut fs::File) -> Option<usize> {
+    use std::io::{Seek, SeekFrom};
+    let compressed = file.metadata().ok()?.len();
+    let mut isize = [0u8; 4];
+    file.seek(SeekFrom::End(-4)).ok()?;
+    file.read_exact(&mut isize).ok()?;
+    file.seek(SeekFrom::Start(0)).ok()?;
+    let size = u32::from_le_bytes(isize) as u64;
+    // Text never shrinks, and doesn't compress much better than 20:1
+    (size >= compressed && size <= compressed.saturating_mul(20)).then_some(size as usize)
+}
+
+/// Write an RFC to the cache, creating the cache directory on first use.
+pub fn write_cached_rfc(number: u32, content: &str) -> std::io::Result<()> {
+    fs::create_dir_all(cache_dir())?;
+    write_gzip_atomic(&rfc_cache_path(number), content)
+}
+
+/// Replace `path` with `contents` by way of a temporary file next to it and a
+/// rename, so another rfcli reading (or writing) it at the same time sees the
+/// old file or the new one, never half of one.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    write_atomic_with(path, |file| file.write_all(contents.as_ref()))
+}
+
+/// `write_atomic` for text kept gzipped, like the cached RFCs.
+pub fn write_gzip_atomic(path: &Path, text: &str) -> std::io::Result<()> {
+    write_atomic_with(path, |file| {
+        let mut encoder = GzEncoder::new(file, flate2::Compression::default());
+        encoder.write_all(text.as_bytes())?;
+        encoder.finish().map(|_| ())
+    })
+}
+
+fn write_atomic_with(path: &Path, write: impl FnOnce(&mut fs::File) -> std::io::Result<()>) -> std::io::Result<()> {
+    // Unique per process and per write, so concurrent writers never share a temporary file
+    static WRITES: AtomicUsize = AtomicUsize::new(0);
+    let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let temp = path.with_file_name(format!(
+        ".{}.{}-{}.tmp",
+        name,
+        std::process::id(),
+        WRITES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let written = fs::File::create(&temp).and_then(|mut file| write(&mut file)).and_then(|_| fs::rename(&temp, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    written
+}
+
+/// The rfc-editor.org metadata of an RFC (`rfc{n}.json`), cached next to its
+/// text. A cached copy is used while younger than the index TTL, the
+/// relations in it change when newer RFCs come out, and for good offline
+/// or when asking again fails.
+pub async fn fetch_metadata(number: u32, offline: bool) -> Result<metadata::Metadata, Error> {
+    let cache_path = cache_dir().join(format!("rfc{}.json", number));
+    let cached = || serde_json::from_str::<metadata::Metadata>(&fs::read_to_string(&cache_path).ok()?).ok();
+    let ttl_days = config::get().index_ttl_days.unwrap_or(DEFAULT_INDEX_TTL_DAYS);
+    let fresh = index_age(&cache_path).is_some_and(|age| age <= std::time::Duration::from_secs(ttl_days * 24 * 60 * 60));
+    if offline || fresh {
+        return cached().ok_or_else(|| Error::NotCached(format!("the metadata of RFC {} is not cached", number)));
+    }
+
+    let url = format!("https://www.rfc-editor.org/rfc/rfc{}.json", number);
+    let raw = match net::get(&url).await {
+        Ok(response) if response.status().is_success() => response.text().await.map_err(|e| e.to_string()),
+        Ok(response) => Err(format!("the metadata of RFC {} could not be fetched ({})", number, response.status())),
+        Err(e) => Err(e.to_string()),
+    };
+    let parsed = raw.and_then(|raw| {
+        let parsed: metadata::Metadata = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+        Ok((raw, parsed))
+    });
+    match parsed {
+        Ok((raw, parsed)) => {
+            // Unsaved, it's only asked for again next time
+            let _ = fs::create_dir_all(cache_dir()).and_then(|_| write_atomic(&cache_path, raw));
+            Ok(parsed)
+        }
+        Err(e) => cached().ok_or(Error::Download(e)),
+    }
+}
+
+/// Numbers of every RFC with a cached text file, ascending.
+pub fn cached_rfc_numbers() -> Vec<u32> {
+    let rfc_re = Regex::new(r"^rfc(\d+)\.txt\.gz$").unwrap();
+    let mut numbers: Vec<u32> = fs::read_dir(cache_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let name = entry.file_name();
+                    let caps = rfc_re.captures(name.to_str()?)?;
+                    caps[1].parse().ok()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    numbers.sort_unstable();
+    numbers
+}
+
+/// The raw text of an RFC, from the cache or downloaded into it. Offline,
+/// only cached RFCs are there.
+pub async fn fetch_rfc(number: u32, offline: bool) -> Result<String, Error> {
+    let cache_path = rfc_cache_path(number);
+
+    // If it's in the cache, read it!
+    if cache_path.exists() {
+        return read_cached_rfc(number)
+            .map_err(|e| Error::Cache(format!("could not read RFC {} from {}: {}", number, cache_path.display(), e)));
+    }
+
+    // A fresh index knows every issued number, so typos never reach the network.
+    // A stale one may just predate the RFC, then the download itself decides.
+    if let Some(entries) = known_entries(offline) {
+        match index::find(&entries, number) {
+            None => return Err(Error::NotFound(format!("RFC {} does not exist", number))),
+            Some(entry) if entry.title == "Not Issued" => {
+                return Err(Error::NotFound(format!("RFC {} was never issued", number)));
+            }
+            Some(_) => {}
+        }
+    }
+
+    if offline {
+        return Err(Error::NotCached(format!("RFC {} is not cached (offline mode)", number)));
+    }
+
+    // Otherwise, fetch and save it
+    let file = format!("rfc{}.txt", number);
+    let mut attempt = 0;
+    let (content, mirror) = loop {
+        let (response, mirror) = net::get_mirrored(&file, Default::default(), true).await?;
+
+        // A 404 page is not an RFC, never hand it back (or cache it)
+        if !response.status().is_success() {
+            let message = format!("RFC {} could not be fetched ({})", number, response.status());
+            return Err(match response.status() {
+                reqwest::StatusCode::NOT_FOUND => Error::NotFound(message),
+                _ => Error::Download(message),
+            });
+        }
+
+        let content = download_with_progress(response, &format!("Fetching RFC {}", number)).await?;
+        if !looks_like_rfc(&content) || looks_complete(&content) {
+            break (content, mirror);
+        }
+        // Half an RFC in the cache would look fine until someone reads to the end
+        if attempt == 0 {
+            notice!("{}", format!("RFC {} looks truncated, downloading it again...", number).yellow());
+            attempt += 1;
+            continue;
+        }
+        return Err(Error::Download(format!("RFC {} came back truncated twice ({} bytes), not caching it", number, content.len())));
+    };
+
+    // Save for next time, unless the server handed us an HTML page instead of text
+    if looks_like_rfc(&content) {
+        match write_cached_rfc(number, &content) {
+            Ok(()) => record_source(&file, &mirror),
+            Err(e) => notice!("{}: could not cache RFC {} at {}: {}", "Warning".yellow(), number, cache_path.display(), e),
+        }
+    }
+
+    Ok(content)
+}
+
+/// `fetch_rfc` without any output, for downloads in the background (while
+/// a pager has the terminal, say). True when the RFC is cached now.
+pub async fn prefetch_rfc(number: u32) -> bool {
+    let file = format!("rfc{}.txt", number);
+    let Ok((response, mirror)) = net::get_mirrored(&file, Default::default(), false).await else { return false };
+    if !response.status().is_success() {
+        return false;
+    }
+    let Ok(content) = response.text().await else { return false };
+    if !(looks_like_rfc(&content) && looks_complete(&content) && write_cached_rfc(number, &content).is_ok()) {
+        return false;
+    }
+    record_source(&file, &mirror);
+    true
+}
+
+/// The HTML edition of an RFC, cached under `html/`. From RFC 8650 on it is
+/// generated from the XML source, older RFCs only have an htmlized copy of
+/// their text.
+pub async fn fetch_rfc_html(number: u32, offline: bool) -> Result<String, Error> {
+    let cache_path = cache_dir().join("html").join(format!("rfc{}.html.gz", number));
+    if cache_path.exists() {
+        let mut html = String::new();
+        return fs::File::open(&cache_path)
+            .and_then(|file| GzDecoder::new(file).read_to_string(&mut html))
+            .map(|_| html)
+            .map_err(|e| Error::Cache(format!("could not read RFC {} from {}: {}", number, cache_path.display(), e)));
+    }
+    if offline {
+        return Err(Error::NotCached(format!("the HTML edition of RFC {} is not cached (offline mode)", number)));
+    }
+
+    let file = format!("rfc{}.html", number);
+    let (response, mirror) = net::get_mirrored(&file, Default::default(), true).await?;
+    if !response.status().is_success() {
+        let message = format!("the HTML edition of RFC {} could not be fetched ({})", number, response.status());
+        return Err(match response.status() {
+            reqwest::StatusCode::NOT_FOUND => Error::NotFound(message),
+            _ => Error::Download(message),
+        });
+    }
+    let html = download_with_progress(response, &format!("Fetching RFC {} (HTML)", number)).await?;
+
+    let written = fs::create_dir_all(cache_dir().join("html")).and_then(|_| write_gzip_atomic(&cache_path, &html));
+    match written {
+        Ok(()) => record_source(&file, &mirror),
+        Err(e) => notice!("{}: could not cache RFC {} at {}: {}", "Warning".yellow(), number, cache_path.display(), e),
+    }
+    Ok(html)
+}
+
+static DRAFT_REV_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(draft-[a-z0-9-]+?)(?:-(\d{2}))?(?:\.txt)?$").unwrap());
+
+/// Fetch an internet-draft, returning its full name with the revision and its text.
+/// Without a revision (or with `latest`) the newest one is looked up on the
+/// datatracker, or offline, taken from the newest cached one.
+pub async fn fetch_draft(name: &str, latest: bool, offline: bool) -> Result<(String, String), Error> {
+    let name = name.trim().to_ascii_lowercase();
+    let Some(caps) = DRAFT_REV_RE.captures(&name) else {
+        return Err(Error::NotFound(format!("'{}' is not an internet-draft name (try draft-ietf-quic-transport-34)", name)));
+    };
+    let base = caps[1].to_string();
+    let rev = match caps.get(2).map(|m| m.as_str().to_string()) {
+        Some(rev) if !latest => rev,
+        _ if offline => newest_cached_draft(&base)
+            .ok_or_else(|| Error::NotCached(format!("{} is not cached (offline mode)", base)))?,
+        _ => latest_draft_rev(&base).await?,
+    };
+    let name = format!("{}-{}", base, rev);
+
+    let cache_path = cache_dir().join("drafts").join(format!("{}.txt.gz", name));
+    if cache_path.exists() {
+        let mut text = String::new();
+        return fs::File::open(&cache_path)
+            .and_then(|file| GzDecoder::new(file).read_to_string(&mut text))
+            .map(|_| (name.clone(), text))
+            .map_err(|e| Error::Cache(format!("could not read {} from {}: {}", name, cache_path.display(), e)));
+    }
+    if offline {
+        return Err(Error::NotCached(format!("{} is not cached (offline mode)", name)));
+    }
+
+    let response = net::get(&format!("https://www.ietf.org/archive/id/{}.txt", name)).await?;
+    if !response.status().is_success() {
+        let message = format!("{} could not be fetched ({})", name, response.status());
+        return Err(match response.status() {
+            reqwest::StatusCode::NOT_FOUND => Error::NotFound(message),
+            _ => Error::Download(message),
+        });
+    }
+    let content = download_with_progress(response, &format!("Fetching {}", name)).await?;
+
+    if looks_like_rfc(&content) {
+        let written = fs::create_dir_all(cache_dir().join("drafts")).and_then(|_| write_gzip_atomic(&cache_path, &content));
+        if let Err(e) = written {
+            notice!("{}: could not cache {} at {}: {}", "Warning".yellow(), name, cache_path.display(), e);
+        }
+    }
+
+    Ok((name, content))
+}
+
+/// The current revision of a draft according to the datatracker, e.g. "34".
+async fn latest_draft_rev(base: &str) -> Result<String, Error> {
+    let url = format!("https://datatracker.ietf.org/api/v1/doc/document/{}/?format=json", base);
+    let response = net::get(&url).await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(Error::NotFound(format!("the datatracker doesn't know {}", base)));
+    }
+    if !response.status().is_success() {
+        return Err(Error::Download(format!(
+            "could not look up the latest revision of {} ({})",
+            base,
+            response.status()
+        )));
+    }
+    let doc: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| Error::Download(format!("the datatracker sent an unreadable answer for {}: {}", base, e)))?;
+    match doc["rev"].as_str() {
+        Some(rev) => Ok(rev.to_string()),
+        None => Err(Error::Download(format!("the datatracker sent no revision for {}", base))),
+    }
+}
+
+/// The highest revision of `base` in the drafts cache.
+fn newest_cached_draft(base: &str) -> Option<String> {
+    fs::read_dir(cache_dir().join("drafts"))
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.strip_suffix(".txt.gz")?.to_string();
+            let caps = DRAFT_REV_RE.captures(&name)?;
+            (caps[1] == *base).then(|| caps.get(2).map(|m| m.as_str().to_string()))?
+        })
+        .max()
+}
+
+/// The errata list for an RFC as rfc-editor.org publishes it, cached under `errata/`.
+pub async fn fetch_errata(number: u32, refresh: bool, offline: bool) -> Result<Vec<serde_json::Value>, Error> {
+    let cache_path = cache_dir().join("errata").join(format!("rfc{}.json", number));
+
+    if cache_path.exists() && (!refresh || offline) {
+        let unreadable = |e: &dyn std::fmt::Display| {
+            Error::Cache(format!("could not read the errata of RFC {} from {}: {}", number, cache_path.display(), e))
+        };
+        let raw = fs::read_to_string(&cache_path).map_err(|e| unreadable(&e))?;
+        return serde_json::from_str(&raw).map_err(|e| unreadable(&e));
+    }
+
+    let raw = if offline {
+        return Err(Error::NotCached(format!("errata for RFC {} are not cached (offline mode)", number)));
+    } else {
+        let failed = |e: &dyn std::fmt::Display| Error::Download(format!("errata for RFC {} could not be fetched ({})", number, e));
+        let url = format!("https://www.rfc-editor.org/errata/rfc{}.json", number);
+        let response = net::get(&url).await.map_err(|e| failed(&e))?;
+        // RFCs without errata have no errata document
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            "[]".to_string()
+        } else if !response.status().is_success() {
+            return Err(failed(&response.status()));
+        } else {
+            response.text().await.map_err(|e| failed(&e))?
+        }
+    };
+
+    let errata: Vec<serde_json::Value> = serde_json::from_str(&raw)
+        .map_err(|e| Error::Download(format!("the errata of RFC {} are not valid JSON: {}", number, e)))?;
+    if let Some(parent) = cache_path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = write_atomic(&cache_path, &raw);
+    Ok(errata)
+}
+
+/// Older versions cached plain `rfc{n}.txt` files. Compress any that are
+/// left over, returning how many there were.
+pub fn compress_plain_cache() -> usize {
+    let plain_re = Regex::new(r"^rfc(\d+)\.txt$").unwrap();
+    let Ok(entries) = fs::read_dir(cache_dir()) else { return 0 };
+
+    let mut compressed = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(number) = name.to_str().and_then(|n| plain_re.captures(n)).and_then(|caps| caps[1].parse().ok()) else {
+            continue;
+        };
+        let Ok(content) = fs::read_to_string(entry.path()) else { continue };
+        if write_cached_rfc(number, &content).is_ok() && fs::remove_file(entry.path()).is_ok() {
+            compressed += 1;
+        }
+    }
+    compressed
+}
+
+/// Progress bars that may still be on screen, for `clear_progress`.
+static PROGRESS: Mutex<Vec<WeakProgressBar>> = Mutex::new(Vec::new());
+
+/// Remember `pb` so `clear_progress` can take it off the screen.
+pub fn track_progress(pb: &ProgressBar) {
+    let mut bars = PROGRESS.lock().unwrap_or_else(PoisonError::into_inner);
+    bars.retain(|bar| bar.upgrade().is_some_and(|bar| !bar.is_finished()));
+    bars.push(pb.downgrade());
+}
+
+/// Clear every tracked progress bar still drawing, when the program is cut
+/// short (Ctrl-C) and won't get to finish them itself.
+pub fn clear_progress() {
+    let bars = std::mem::take(&mut *PROGRESS.lock().unwrap_or_else(PoisonError::into_inner));
+    for bar in bars.iter().filter_map(WeakProgressBar::upgrade) {
+        bar.finish_and_clear();
+    }
+}
+
+/// Read a response body, showing bytes and rate as it comes in when
+/// reporting. A bar when the server sends a Content-Length, a spinner otherwise. Gives up once nothing
+/// has arrived for the configured timeout, however long the whole takes.
+pub async fn download_with_progress(mut response: reqwest::Response, message: &str) -> Result<String, Error> {
+    let pb = match response.content_length() {
+        Some(len) => {
+            let pb = ProgressBar::new(len);
+            pb.set_style(ProgressStyle::default_bar()
+                .template("{spinner:.magenta} {msg} [{bar:30.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec})")
+                .unwrap()
+                .progress_chars("=> "));
+            pb
+        }
+        None => {
+            let pb = ProgressBar::new_spinner();
+            pb.set_style(ProgressStyle::default_spinner()
+                .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"])
+                .template("{spinner:.magenta} {msg} {bytes} ({bytes_per_sec})")
+                .unwrap());
+            pb
+        }
+    };
+    if !reporting() {
+        pb.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    pb.set_message(message.to_string());
+    pb.enable_steady_tick(std::time::Duration::from_millis(120));
+    track_progress(&pb);
+
+    let mut body = Vec::with_capacity(response.content_length().unwrap_or(0) as usize);
+    loop {
+        // No deadline for the whole body, only for the wait on each chunk
+        match tokio::time::timeout(net::timeout(), response.chunk()).await {
+            Ok(Ok(Some(chunk))) => {
+                body.extend_from_slice(&chunk);
+                pb.inc(chunk.len() as u64);
+            }
+            Ok(Ok(None)) => break,
+            Ok(Err(e)) => {
+                pb.finish_and_clear();
+                return Err(Error::Http(e.to_string()));
+            }
+            Err(_) => {
+                pb.finish_and_clear();
+                return Err(Error::Http(format!("the download stalled for {:?}", net::timeout())));
+            }
+        }
+    }
+    pb.finish_and_clear();
+
+    // Valid UTF-8 (nearly always) becomes the String without a copy
+    Ok(String::from_utf8(body).unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned()))
+}
+
+pub fn looks_like_rfc(body: &str) -> bool {
+    let start: String = body.trim_start().chars().take(16).collect::<String>().to_ascii_lowercase();
+    !start.is_empty() && !start.starts_with("<!doctype") && !start.starts_with("<html")
+}
+
+/// Smaller bodies than this are cut-off downloads, even the oldest one-page RFCs are longer.
+const MIN_RFC_BYTES: usize = 500;
+
+/// Whether a downloaded RFC ends the way RFCs end: on its last page footer,
+/// or, in the unpaginated RFCs since 8650, with the authors' addresses or the
+/// copyright statement in its last lines. Old RFCs typed in later end on a
+/// note saying so. A download that stops mid-page has none of these.
+pub fn looks_complete(body: &str) -> bool {
+    if body.len() < MIN_RFC_BYTES {
+        return false;
+    }
+    let tail: Vec<&str> = body.lines().rev().map(str::trim).filter(|line| !line.is_empty()).take(150).collect();
+    let Some(last) = tail.first() else { return false };
+    FOOTER_RE.is_match(last)
+        || tail.iter().take(3).any(|line| line.contains("machine readable form"))
+        || tail.iter().any(|line| CLOSING_SECTION_RE.is_match(line))
+}
+
+// "Authors' Addresses", "12.  Author's Address", "Appendix C.  Full Copyright Statement"
+static CLOSING_SECTION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:(?:appendix\s+)?[a-z0-9]+(?:\.[a-z0-9]+)*\.?\s+)?(?:(?:author|editor)(?:'s|s'|s)?\s+address(?:es)?|full copyright statement)$").unwrap()
+});
+
+// Footers end in "[Page N]". Running headers look like "RFC 2616    HTTP/1.1    June 1999"
+// ("Internet-Draft    QUIC    March 2021" in drafts) and are only dropped right after
+// a page break, so body text and reference lists that happen to start with "RFC 2616" survive.
+static FOOTER_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[Page \d+\]\s*$").unwrap());
+
+static HEADER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:RFC \d+|Internet-Draft)\s{2,}.*\s(?:January|February|March|April|May|June|July|August|September|October|November|December) \d{4}\s*$").unwrap()
+});
+
+pub fn clean_rfc_text(raw_text: &str) -> String {
+    let mut out = String::with_capacity(raw_text.len());
+    for_each_clean_line(raw_text, |line| {
+        out.push_str(line);
+        out.push('\n');
+    });
+    out
+}
+
+/// The cleaning of `clean_rfc_text` one line at a time, blank lines as "",
+/// for callers that can use the lines as they come instead of holding a
+/// second copy of a multi-megabyte RFC.
+pub fn for_each_clean_line(raw_text: &str, mut emit: impl FnMut(&str)) {
+    let mut blanks = 0;
+    let mut page_break = false;
+    // A page has one running header, so only the first line after the break can be it
+    let mut header_due = false;
+    let mut prev_figure = false;
+
+    for raw_line in raw_text.lines() {
+        // Only the rare line with a form feed needs a copy
+        let line: std::borrow::Cow<str> = if raw_line.contains('\x0C') {
+            page_break = true;
+            header_due = true;
+            raw_line.replace('\x0C', "").into()
+        } else {
+            raw_line.into()
+        };
+        let line = line.as_ref();
+        if FOOTER_RE.is_match(line) {
+            page_break = true;
+            header_due = true;
+            continue;
+        }
+        if header_due && HEADER_RE.is_match(line) {
+            header_due = false;
+            continue;
+        }
+        if line.trim().is_empty() {
+            blanks += 1;
+            continue;
+        }
+
+        // Blank runs in prose collapse to a single line, but spacing between two
+        // lines of the same figure or table is part of the drawing. Page breaks
+        // are never part of a drawing.
+        let figure = is_figure_line(line);
+        let keep = if prev_figure && figure && !page_break { blanks } else { blanks.min(1) };
+        for _ in 0..keep {
+            emit("");
+        }
+        emit(line);
+
+        blanks = 0;
+        page_break = false;
+        header_due = false;
+        prev_figure = figure;
+    }
+}
+
+/// Heuristic for lines belonging to ASCII art, diagrams or column-aligned tables:
+/// box-drawing characters, arrows, or a run of three or more spaces after the indentation.
+pub fn is_figure_line(line: &str) -> bool {
+    const BOX_CHARS: &[char] = &['|', '─', '│', '┌', '┐', '└', '┘', '├', '┤', '┬', '┴', '┼'];
+    let body = line.trim_start();
+    body.contains(BOX_CHARS)
+        || body.contains("+-")
+        || body.contains("-+")
+        || body.contains("-->")
+        || body.contains("<--")
+        || body.trim_end().contains("   ")
+}
+
+/// Default `index_ttl_days`: an older cached index is refreshed automatically.
+pub const DEFAULT_INDEX_TTL_DAYS: u64 = 7;
+
+/// Read the cached RFC index, downloading it first if needed.
+pub async fn load_index(force_refresh: bool, offline: bool) -> Result<String, Error> {
+    load_index_file("rfc-index.txt", "RFC index", force_refresh, offline).await
+}
+
+/// Read one of the rfc-editor.org index files from the cache, downloading it
+/// first when it is missing, past its TTL or `force_refresh` is set.
+/// `label` names it in messages ("RFC index", "STD index"). When the
+/// download fails (`Error::Download`) a cached copy may still be there,
+/// `read_cached_index` reads it regardless of its age.
+pub async fn load_index_file(name: &str, label: &str, force_refresh: bool, offline: bool) -> Result<String, Error> {
+    let cache_dir = cache_dir();
+    let index_path = cache_dir.join(name);
+
+    if let Err(e) = fs::create_dir_all(&cache_dir) {
+        return Err(Error::Cache(format!("could not create the cache directory {}: {}", cache_dir.display(), e)));
+    }
+
+    if offline && !index_path.exists() {
+        return Err(Error::NotCached(format!("{} is not cached (offline mode)", label)));
+    }
+
+    // The file's mtime is the download time
+    let ttl_days = config::get().index_ttl_days.unwrap_or(DEFAULT_INDEX_TTL_DAYS);
+    let stale = index_age(&index_path)
+        .is_some_and(|age| age > std::time::Duration::from_secs(ttl_days * 24 * 60 * 60));
+
+    // Download if it doesn't exist, is past its TTL, OR if user passed the -r flag
+    if !offline && (!index_path.exists() || force_refresh || stale) {
+        if stale && !force_refresh {
+            notice!("{}", format!("{} is older than {} days, refreshing...", label, ttl_days).yellow());
+        } else {
+            notice!("{}", format!("Updating {} from IETF...", label).yellow());
+        }
+        let failed = |reason: &dyn std::fmt::Display| Error::Download(format!("could not download the {}: {}", label, reason));
+        // Only ask for the file if it changed since the copy we have
+        let validators = index_validators().remove(name).filter(|_| index_path.exists()).unwrap_or_default();
+        // Transient failures were already retried with backoff by then
+        let (response, mirror) = net::get_mirrored(name, validators.headers(), true).await.map_err(|e| failed(&e))?;
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            // Restart the TTL, the cached copy is as new as it gets
+            if let Err(e) = fs::File::options().append(true).open(&index_path).and_then(|f| f.set_modified(std::time::SystemTime::now())) {
+                notice!("{}: could not update {}: {}", "Warning".yellow(), index_path.display(), e);
+            }
+            notice!("{}", format!("{} is already up to date.", label).green());
+            return read_cached_index(name, label);
+        }
+        if !response.status().is_success() {
+            return Err(failed(&format!("the server answered {}", response.status())));
+        }
+        let validators = IndexValidators::from_response(&response);
+        let content = download_with_progress(response, "Downloading index").await.map_err(|e| failed(&e))?;
+        // Unsaved, the index still works for this run
+        if let Err(e) = write_atomic(&index_path, &content) {
+            notice!("{}: could not save the {} to {}: {}", "Warning".yellow(), label, index_path.display(), e);
+            return Ok(content);
+        }
+        if name == "rfc-index.txt" {
+            // Parsed from the old index, the fingerprint would catch it but there's no point keeping it
+            let _ = fs::remove_file(cache_dir.join("rfc-index.json"));
+        }
+        save_index_validators(name, validators);
+        record_source(name, &mirror);
+        notice!("{}", "Index updated successfully.".green());
+        return Ok(content);
+    }
+
+    read_cached_index(name, label)
+}
+
+/// The cached index file `name`, however old.
+pub fn read_cached_index(name: &str, label: &str) -> Result<String, Error> {
+    let index_path = cache_dir().join(name);
+    fs::read_to_string(&index_path)
+        .map_err(|e| Error::Cache(format!("could not read the {} at {}: {}", label, index_path.display(), e)))
+}
+
+/// `ETag` and `Last-Modified` of a downloaded index file, sent back as
+/// `If-None-Match`/`If-Modified-Since` so an unchanged index costs a 304.
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct IndexValidators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl IndexValidators {
+    fn from_response(response: &reqwest::Response) -> IndexValidators {
+        let header = |name| response.headers().get(name).and_then(|v| v.to_str().ok()).map(String::from);
+        IndexValidators {
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+        }
+    }
+
+    fn headers(&self) -> reqwest::header::HeaderMap {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(etag) = self.etag.as_deref().and_then(|v| v.parse().ok()) {
+            headers.insert(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(date) = self.last_modified.as_deref().and_then(|v| v.parse().ok()) {
+            headers.insert(reqwest::header::IF_MODIFIED_SINCE, date);
+        }
+        headers
+    }
+}
+
+/// `index-meta.json`: the validators of every cached index file, by file name.
+fn index_validators() -> std::collections::HashMap<String, IndexValidators> {
+    fs::read_to_string(cache_dir().join("index-meta.json"))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_index_validators(name: &str, validators: IndexValidators) {
+    let mut all = index_validators();
+    all.insert(name.to_string(), validators);
+    if let Ok(json) = serde_json::to_string_pretty(&all) {
+        // Losing these only costs one full download next time
+        let _ = write_atomic(&cache_dir().join("index-meta.json"), json);
+    }
+}
+
+/// `sources.json`: which mirror each downloaded file ("rfc2616.txt",
+/// "rfc-index.txt") came from, by file name.
+pub fn download_sources() -> std::collections::HashMap<String, String> {
+    fs::read_to_string(cache_dir().join("sources.json"))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn record_source(file: &str, mirror: &str) {
+    let mut all = download_sources();
+    all.insert(file.to_string(), mirror.to_string());
+    if let Ok(json) = serde_json::to_string_pretty(&all) {
+        // Only informational, `info` and `status` just show less without it
+        let _ = write_atomic(&cache_dir().join("sources.json"), json);
+    }
+}
+
+/// The cached index, if it's recent enough to tell a typo from a brand-new RFC
+/// (any cached index will do offline, there is nothing newer to ask for).
+pub fn known_entries(offline: bool) -> Option<Vec<index::RfcEntry>> {
+    let index_path = cache_dir().join("rfc-index.txt");
+    let ttl_days = config::get().index_ttl_days.unwrap_or(DEFAULT_INDEX_TTL_DAYS);
+    let fresh = index_age(&index_path).is_some_and(|age| age <= std::time::Duration::from_secs(ttl_days * 24 * 60 * 60));
+    if !fresh && !offline {
+        return None;
+    }
+    let entries = index_entries(&fs::read_to_string(index_path).ok()?);
+    (!entries.is_empty()).then_some(entries)
+}
+
+/// Hand the index entries to `f` one at a time, until it returns false.
+/// The parse is kept (in the store with the `sqlite` feature, in
+/// `rfc-index.json` otherwise) and only redone after `rfc-index.txt` changes.
+pub fn each_index_entry(index_data: &str, mut f: impl FnMut(index::RfcEntry) -> bool) {
+    let fingerprint = index_fingerprint();
+    #[cfg(feature = "sqlite")]
+    if let Some(mut store) = open_store() {
+        if let Ok(Some(entries)) = store.entries(&fingerprint) {
+            for entry in entries {
+                if !f(entry) {
+                    break;
+                }
+            }
+            return;
+        }
+        let entries = parse_all_entries(index_data, f);
+        if let Err(e) = store.save_entries(&entries, &fingerprint) {
+            notice!("{}: could not store the parsed index: {}", "Warning".yellow(), e);
+        }
+        return;
+    }
+
+    // Without SQLite the parsed index is kept as JSON next to the text
+    if fingerprint.is_empty() {
+        index::for_each_entry(index_data, f);
+        return;
+    }
+    let parsed_path = cache_dir().join("rfc-index.json");
+    if let Some(entries) = index::load_parsed(&parsed_path, &fingerprint) {
+        for entry in entries {
+            if !f(entry) {
+                break;
+            }
+        }
+        return;
+    }
+    let entries = parse_all_entries(index_data, f);
+    if let Err(e) = index::save_parsed(&parsed_path, &fingerprint, &entries) {
+        notice!("{}: could not save the parsed index: {}", "Warning".yellow(), e);
+    }
+}
+
+/// Every entry of the index, parsed in full even if `f` is done early so
+/// the copy kept for next time is complete.
+fn parse_all_entries(index_data: &str, mut f: impl FnMut(index::RfcEntry) -> bool) -> Vec<index::RfcEntry> {
+    let mut entries = Vec::new();
+    let mut wanted = true;
+    index::for_each_entry(index_data, |entry| {
+        if wanted {
+            wanted = f(entry.clone());
+        }
+        entries.push(entry);
+        true
+    });
+    entries
+}
+
+/// All entries of the index, from the parsed copy when it is still current.
+pub fn index_entries(index_data: &str) -> Vec<index::RfcEntry> {
+    let mut entries = Vec::new();
+    each_index_entry(index_data, |entry| {
+        entries.push(entry);
+        true
+    });
+    entries
+}
+
+/// Identifies the downloaded `rfc-index.txt`: size and mtime change with
+/// every download, that's all a parsed copy needs to spot a new index.
+/// Empty when there is no index file.
+fn index_fingerprint() -> String {
+    fs::metadata(cache_dir().join("rfc-index.txt"))
+        .ok()
+        .and_then(|meta| Some(format!("{}:{:?}", meta.len(), meta.modified().ok()?)))
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "sqlite")]
+pub fn open_store() -> Option<store::Store> {
+    let dir = cache_dir();
+    fs::create_dir_all(&dir).ok()?;
+    match store::Store::open(&dir.join("rfcli.db")) {
+        Ok(store) => Some(store),
+        Err(e) => {
+            notice!("{}: could not open {}: {}", "Warning".yellow(), dir.join("rfcli.db").display(), e);
+            None
+        }
+    }
+}
+
+/// How long ago the cached index was downloaded.
+pub fn index_age(index_path: &std::path::Path) -> Option<std::time::Duration> {
+    fs::metadata(index_path).ok()?.modified().ok()?.elapsed().ok()
+}
+
+pub const TLDR_SYSTEM_PROMPT: &str = "You are a Senior Systems Engineer. Summarize the RFC for a terminal UI. DO NOT use Markdown bolding (no asterisks). Use a simple 'TITLE: description' format for bullets. Keep the elevator pitch at the top.";
+
+pub const CHUNK_SYSTEM_PROMPT: &str = "You are a Senior Systems Engineer. Summarize this excerpt of an RFC as a few terse bullet points covering the concrete mechanisms, requirements and definitions it contains. No preamble.";
+
+/// Rough per-chunk budget for `--full` mode, counted as ~4 characters per token.
+pub const CHUNK_TOKENS: usize = 4000;
+
+/// Split text into chunks of at most `max_chars`, breaking between paragraphs
+/// where possible so no chunk starts mid-sentence.
+pub fn split_into_chunks(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in text.split("\n\n") {
+        if !current.is_empty() && current.len() + paragraph.len() + 2 > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if paragraph.len() > max_chars {
+            // A single huge paragraph (a long table, say) gets cut by lines instead
+            for line in paragraph.lines() {
+                if !current.is_empty() && current.len() + line.len() + 1 > max_chars {
+                    chunks.push(std::mem::take(&mut current));
+                }
+                current.push_str(line);
+                current.push('\n');
+            }
+        } else {
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(paragraph);
+        }
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+/// The text a summary is made from: the cleaned RFC without its
+/// boilerplate and table of contents, which would crowd the first 300 lines.
+pub fn tldr_context(raw_text: &str) -> String {
+    sections::strip_boilerplate(&clean_rfc_text(raw_text))
+}
+
+/// The request for a one-shot summary: the first 300 lines of the context.
+/// `subject` says what it is, "RFC 9110" or a section of one.
+pub fn tldr_prompt(subject: &str, context: &str) -> String {
+    let head = context.lines().take(300).collect::<Vec<_>>().join("\n");
+    format!("Summarize {}:\n\n{}", subject, head)
+}
+
+/// `--full`: one request per chunk of the context, each answered under `CHUNK_SYSTEM_PROMPT`.
+pub fn chunk_prompts(subject: &str, context: &str) -> Vec<String> {
+    let chunks = split_into_chunks(context, CHUNK_TOKENS * 4);
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| format!("Part {} of {} of {}:\n\n{}", i + 1, chunks.len(), subject, chunk))
+        .collect()
+}
+
+/// `--full`: the final request, built from the answers to `chunk_prompts`.
+pub fn combine_prompt(subject: &str, partials: &[String]) -> String {
+    format!("Summarize {} from these notes on each of its parts:\n\n{}", subject, partials.join("\n\n"))
+}
+
+/// Summarize RFC `number` from its raw text.
+/// `full` summarizes every part of the document and combines the results,
+/// otherwise the summary comes from its beginning. Token usage is summed
+/// over every request, None when any of them came back without counts.
+pub async fn summarize(
+    number: u32,
+    raw_text: &str,
+    endpoint: &Endpoint,
+    system_prompt: &str,
+    full: bool,
+    max_tokens: Option<u32>,
+) -> Result<Reply, Error> {
+    let request = SummaryRequest {
+        subject: &format!("RFC {}", number),
+        context: &tldr_context(raw_text),
+        system_prompt,
+        full,
+        max_tokens,
+        include_usage: true,
+    };
+    // Every request is sent, so there is always a reply
+    summarize_with(endpoint, &request, |_| true, |_| {})
+        .await?
+        .ok_or_else(|| Error::Api("no summary was asked for".to_string()))
+}
+
+/// A summary for `summarize_with` to ask for.
+pub struct SummaryRequest<'a> {
+    /// What the context is, "RFC 9110" or a section of one
+    pub subject: &'a str,
+    /// The text to summarize, see `tldr_context`
+    pub context: &'a str,
+    /// For the final summary, the parts of a `full` one use `CHUNK_SYSTEM_PROMPT`
+    pub system_prompt: &'a str,
+    /// Summarize every part of the context and combine the results
+    pub full: bool,
+    pub max_tokens: Option<u32>,
+    /// Ask for the token counts of the final summary
+    pub include_usage: bool,
+}
+
+/// One request `summarize_with` is about to send.
+pub struct PendingRequest<'a> {
+    /// Part `n` of how many, None for the final summary
+    pub part: Option<(usize, usize)>,
+    pub system: &'a str,
+    pub user: &'a str,
+    pub max_tokens: Option<u32>,
+}
+
+/// `summarize` for callers that show their progress. `before` sees every
+/// request before it is sent and returns false to leave it unsent, the final
+/// summary then isn't asked for either and the result is None (a dry run).
+/// The final summary streams through `on_token` as it arrives.
+pub async fn summarize_with(
+    endpoint: &Endpoint,
+    request: &SummaryRequest<'_>,
+    mut before: impl FnMut(&PendingRequest) -> bool,
+    on_token: impl FnMut(&str),
+) -> Result<Option<Reply>, Error> {
+    let client = net::client()?;
+    let mut usage = Some(Usage::default());
+    let prompt: Cow<str> = if request.full {
+        // Map: summarize every chunk on its own
+        let chunks = chunk_prompts(request.subject, request.context);
+        let mut partials = Vec::with_capacity(chunks.len());
+        let mut sent_all = true;
+        for (i, prompt) in chunks.iter().enumerate() {
+            let pending = PendingRequest { part: Some((i, chunks.len())), system: CHUNK_SYSTEM_PROMPT, user: prompt, max_tokens: None };
+            if !before(&pending) {
+                sent_all = false;
+                continue;
+            }
+            let partial = endpoint.chat(client, CHUNK_SYSTEM_PROMPT, prompt).await?;
+            usage = usage.zip(partial.usage).map(|(total, part)| total + part);
+            partials.push(partial.text);
+        }
+        if !sent_all {
+            return Ok(None);
+        }
+        // Reduce: summarize the summaries into the final one
+        combine_prompt(request.subject, &partials).into()
+    } else {
+        tldr_prompt(request.subject, request.context).into()
+    };
+
+    let pending = PendingRequest { part: None, system: request.system_prompt, user: &prompt, max_tokens: request.max_tokens };
+    if !before(&pending) {
+        return Ok(None);
+    }
+    let reply = endpoint
+        .chat_stream(client, request.system_prompt, &prompt, request.max_tokens, request.include_usage, on_token)
+        .await?;
+    Ok(Some(Reply { usage: usage.zip(reply.usage).map(|(total, last)| total + last), ..reply }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clean_lines(raw_text: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        for_each_clean_line(raw_text, |line| lines.push(line.to_string()));
+        lines
+    }
+
+    #[test]
+    fn drops_page_footers_and_running_headers() {
+        let raw = "   end of page one.
+
+Fielding, et al.            Standards Track                    [Page 1]
+\x0C
+RFC 2616                        HTTP/1.1                       June 1999
+
+   start of page two.
+";
+        assert_eq!(clean_lines(raw), ["   end of page one.", "", "   start of page two."]);
+    }
+
+    #[test]
+    fn keeps_rfc_lines_after_the_running_header() {
+        // A reference list in the "RFC NNNN  Author  Date" layout of the old
+        // index RFCs, continuing at the top of a new page
+        let raw = "   RFC 1034   P. Mockapetris                       November 1987
+
+Postel                                                         [Page 7]
+\x0C
+RFC 1340                    Assigned Numbers                   July 1992
+
+   RFC 1035   P. Mockapetris                       November 1987
+   RFC 1057   Sun Microsystems                     June 1988
+";
+        assert_eq!(
+            clean_lines(raw),
+            [
+                "   RFC 1034   P. Mockapetris                       November 1987",
+                "",
+                "   RFC 1035   P. Mockapetris                       November 1987",
+                "   RFC 1057   Sun Microsystems                     June 1988",
+            ]
+        );
+
+        let unindented = "RFC 1340                    Assigned Numbers                   July 1992
+
+RFC 1057    Sun Microsystems    June 1988
+";
+        let raw = format!("text\n\nPostel                       [Page 7]\n\x0C\n{}", unindented);
+        assert_eq!(clean_lines(&raw), ["text", "", "RFC 1057    Sun Microsystems    June 1988"]);
+    }
+
+    /// The three-way handshake of RFC 793, Figure 7, between two lines of prose.
+    const HANDSHAKE: &str = "   The synchronization requires each side to send its own initial
+   sequence number and to receive a confirmation of it.
+
+
+      TCP A                                                TCP B
+
+  1.  CLOSED                                               LISTEN
+
+  2.  SYN-SENT    --> <SEQ=100><CTL=SYN>               --> SYN-RECEIVED
+
+  3.  ESTABLISHED <-- <SEQ=300><ACK=101><CTL=SYN,ACK>  <-- SYN-RECEIVED
+
+  4.  ESTABLISHED --> <SEQ=101><ACK=301><CTL=ACK>       --> ESTABLISHED
+
+  5.  ESTABLISHED --> <SEQ=101><ACK=301><CTL=ACK><DATA> --> ESTABLISHED
+
+          Basic 3-Way Handshake for Connection Synchronization
+";
+
+    #[test]
+    fn recognizes_box_and_arrow_art() {
+        let header = [
+            "   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+",
+            "   |          Source Port          |       Destination Port        |",
+            "                              +---------+ ---------\\      active OPEN",
+            "                              |  CLOSED |            \\    -----------",
+        ];
+        for line in header {
+            assert!(is_figure_line(line), "{:?}", line);
+        }
+        for line in HANDSHAKE.lines().skip(4).take(11).filter(|line| !line.trim().is_empty()) {
+            assert!(is_figure_line(line), "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn prose_is_not_a_figure() {
+        for line in [
+            "   The synchronization requires each side to send its own initial",
+            "   sequence number and to receive a confirmation of it.  See RFC 793.",
+            "   A well-known port (e.g., 80) names a service, not a host-to-host path.",
+            "",
+        ] {
+            assert!(!is_figure_line(line), "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn keeps_figures_verbatim() {
+        // The two blank lines after the prose collapse to one, the figure is untouched
+        let cleaned = clean_rfc_text(HANDSHAKE);
+        let expected = HANDSHAKE.replacen("confirmation of it.\n\n\n", "confirmation of it.\n\n", 1);
+        assert_eq!(cleaned, expected);
+    }
+
+    /// Two pages as RFC 2616-era RFCs lay them out, the copyright statement and
+    /// authors' addresses on the last. Words like "address" and "Copyright" turn
+    /// up well before the end.
+    fn paginated_rfc() -> String {
+        let mut text = String::from(
+            "Network Working Group                                      R. Fielding
+Request for Comments: 2616                                     June 1999
+
+Copyright Notice
+
+   Copyright (C) The Internet Society (1999).  All Rights Reserved.
+
+1 Introduction
+
+   The Request-URI identifies the resource; its address is given by the
+   Host header field and the net_path of the request.
+",
+        );
+        for n in 0..30 {
+            text.push_str(&format!("   Body text line {} about messages sent to the origin server.\n", n));
+        }
+        text.push_str(
+            "
+Fielding, et al.            Standards Track                    [Page 1]
+\x0C
+RFC 2616                        HTTP/1.1                       June 1999
+
+18 Authors' Addresses
+
+   Roy T. Fielding
+   Email: fielding@ics.uci.edu
+
+19 Full Copyright Statement
+
+   Copyright (C) The Internet Society (1999).  All Rights Reserved.
+
+Fielding, et al.            Standards Track                    [Page 2]
+",
+        );
+        text
+    }
+
+    #[test]
+    fn complete_rfcs_end_on_a_footer_or_closing_section() {
+        let paginated = paginated_rfc();
+        assert!(looks_complete(&paginated));
+        assert!(looks_complete(&format!("{}\x0C\n", paginated)));
+
+        // Since RFC 8650 there are no pages, the authors' addresses come last
+        let unpaginated = paginated.replace("Fielding, et al.            Standards Track                    [Page 2]\n", "");
+        assert!(looks_complete(&unpaginated));
+        let single_author = unpaginated.replace("18 Authors' Addresses", "Author's Address");
+        assert!(looks_complete(&single_author));
+    }
+
+    #[test]
+    fn truncated_downloads_are_incomplete() {
+        let paginated = paginated_rfc();
+        // Cut mid-page, after the boilerplate and a body that mention addresses and copyright
+        let cut = paginated.find("   Body text line 29").unwrap();
+        assert!(!looks_complete(&paginated[..cut]));
+        // Cut right after a page break, before the closing sections
+        let cut = paginated.find("18 Authors' Addresses").unwrap();
+        assert!(!looks_complete(&paginated[..cut]));
+        assert!(!looks_complete(&paginated[..400]));
+    }
+
+    /// A cache directory of this test run's own, installed as the config's.
+    fn test_cache_dir() -> &'static Path {
+        static DIR: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+        DIR.get_or_init(|| {
+            let dir = std::env::temp_dir().join(format!("rfcli-test-{}", std::process::id())).join("cache");
+            let _ = fs::remove_dir_all(dir.parent().unwrap());
+            config::init(config::Config { cache_dir: Some(dir.clone()), ..Default::default() });
+            dir
+        })
+    }
+
+    #[test]
+    fn cached_rfcs_persist_in_a_fresh_cache_dir() {
+        let dir = test_cache_dir();
+        assert_eq!(cache_dir(), dir);
+        assert!(!dir.exists());
+
+        let text = paginated_rfc();
+        write_cached_rfc(2616, &text).unwrap();
+        assert!(dir.is_dir());
+        assert_eq!(rfc_cache_path(2616), dir.join("rfc2616.txt.gz"));
+        assert_eq!(read_cached_rfc(2616).unwrap(), text);
+        assert_eq!(read_cached_rfc(2616).unwrap(), text);
+
+        // Rewriting replaces the file and leaves no temporary file behind
+        let shorter = text.replace("Body text", "Text");
+        write_gzip_atomic(&rfc_cache_path(2616), &shorter).unwrap();
+        assert_eq!(read_cached_rfc(2616).unwrap(), shorter);
+        let names: Vec<_> = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        assert_eq!(names, ["rfc2616.txt.gz"]);
+        assert!(read_cached_rfc(2617).is_err());
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+    }
+
+    #[test]
+    fn concurrent_writers_leave_one_complete_file() {
+        // In the temp dir itself, so the renames stay on one filesystem
+        let dir = std::env::temp_dir().join(format!("rfcli-write-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rfc2616.txt.gz");
+        let texts: Vec<String> = (0..8).map(|n| paginated_rfc().replace("Body text", &format!("Writer {}", n)).repeat(n + 1)).collect();
+        write_gzip_atomic(&path, &texts[0]).unwrap();
+
+        let read_back = |path: &Path| {
+            let mut text = String::new();
+            GzDecoder::new(fs::File::open(path).unwrap()).read_to_string(&mut text).unwrap();
+            text
+        };
+        std::thread::scope(|scope| {
+            for text in &texts {
+                let path = &path;
+                scope.spawn(move || {
+                    for _ in 0..20 {
+                        write_gzip_atomic(path, text).unwrap();
+                    }
+                });
+            }
+            // Readers in the meantime see one writer's text or another's, whole
+            for _ in 0..200 {
+                assert!(texts.contains(&read_back(&path)));
+            }
+        });
+
+        assert!(texts.contains(&read_back(&path)));
+        let names: Vec<_> = fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        assert_eq!(names, ["rfc2616.txt.gz"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,17 +1,33 @@
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
 use skim::prelude::*;
 use regex::Regex;
 use colored::Colorize; 
-use std::process::{Command, Stdio};
-use std::io::Write;
-use std::io::Cursor;
+use std::process::{Command, ExitCode, Stdio};
+use std::io::{IsTerminal, Write};
 use std::fs;
+use std::hash::{BuildHasher, Hasher};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
 use indicatif::{ProgressBar, ProgressStyle};
-use serde_json::json;
 use textwrap::{wrap, Options};
 use inquire::Select;
 
+mod bookmarks;
+mod history;
+mod interrupt;
+mod positions;
+mod viewer;
+
+use rfcli::provider::{self, Endpoint, Provider, Usage};
+use rfcli::{abnf, authors, config, diff, html, index, markdown, net, sections};
+use rfcli::{
+    cache_dir, cached_rfc_numbers, clean_rfc_text, compress_plain_cache, download_sources, each_index_entry,
+    fetch_draft, fetch_errata, fetch_metadata, fetch_rfc, fetch_rfc_html, for_each_clean_line, index_age,
+    index_entries, prefetch_rfc, read_cached_index, read_cached_rfc, rfc_cache_path, summarize_with, tldr_context,
+    track_progress, write_atomic, SummaryRequest, DEFAULT_INDEX_TTL_DAYS, TLDR_SYSTEM_PROMPT,
+};
+#[cfg(feature = "sqlite")]
+use rfcli::open_store;
 
 #[derive(Parser)]
 #[command(name = "rfc")]
@@ -19,258 +35,2307 @@ use inquire::Select;
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+    /// Work purely from the local cache, never touch the network
+    #[arg(long, global = true)]
+    offline: bool,
+    /// Keep downloaded RFCs and the index here (also RFCLI_CACHE_DIR)
+    #[arg(long, global = true, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+    /// Disable colored output (also honors NO_COLOR)
+    #[arg(long, global = true)]
+    no_color: bool,
+    /// Print JSON instead of text (info, list, status, history, tldr), errors as {"error": ...}
+    #[arg(long, global = true)]
+    json: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Search and read an RFC
     Read {
+        /// RFC number (or a subseries like std66, bcp14, or an internet-draft name) to open directly, or an initial search query
+        target: Option<String>,
         /// Force update the local RFC index
         #[arg(short, long)]
         refresh: bool,
+        /// Initial search query for the picker, also highlighted in the document that's opened
         #[arg(short, long)]
         query: Option<String>,
+        /// Pager command to use instead of $PAGER/bat/less
+        #[arg(long)]
+        pager: Option<String>,
+        /// Only list RFCs with these statuses (e.g. standard,proposed,bcp)
+        #[arg(short, long, value_delimiter = ',')]
+        status: Vec<String>,
+        /// Preview pane layout, as in skim (e.g. "right:60%", "down:40%", "hidden")
+        #[arg(long)]
+        preview_window: Option<String>,
+        /// Only show this section (and its subsections), by number or title (e.g. "5.2", "security")
+        #[arg(long)]
+        section: Option<String>,
+        /// Open the pager at this section heading (e.g. "8.1") or the first line containing the text
+        #[arg(short, long)]
+        goto: Option<String>,
+        /// Only offer bookmarked RFCs in the picker
+        #[arg(short, long)]
+        bookmarks: bool,
+        /// Don't warn before opening an obsoleted RFC
+        #[arg(long)]
+        no_warn: bool,
+        /// Print word count, line count and reading time before paging
+        #[arg(long)]
+        stats: bool,
+        /// Open the cleaned text in $VISUAL/$EDITOR instead of a pager
+        #[arg(short, long)]
+        editor: bool,
+        /// Print the cleaned text to stdout instead of paging it, for scripts
+        #[arg(long, conflicts_with = "editor")]
+        stdout: bool,
+        /// Print only the first N cleaned lines to stdout, no pager (implies --stdout)
+        #[arg(long, value_name = "N", conflicts_with_all = ["editor", "tui", "find"])]
+        head: Option<usize>,
+        /// With a draft name, open its newest revision even if one was given
+        #[arg(long)]
+        latest: bool,
+        /// After reading, list the RFCs the document references and offer to open one
+        #[arg(long)]
+        refs: bool,
+        /// Download the RFCs the document references into the cache while you read
+        #[arg(long)]
+        prefetch: bool,
+        /// Only pick from recently read RFCs (with --bookmarks, from bookmarks), without loading the index
+        #[arg(long)]
+        recent: bool,
+        /// Start at the top instead of where you stopped reading last time
+        #[arg(long)]
+        top: bool,
+        /// Read the HTML edition, with styled headings, links and code, instead of the plain text
+        #[arg(long, conflicts_with = "editor")]
+        html: bool,
+        /// Read in the built-in viewer: search, a table of contents and the referenced RFCs one key away
+        #[arg(long, conflicts_with_all = ["editor", "stdout", "html"])]
+        tui: bool,
+        /// Fuzzy-find a line of the document first, and open it there
+        #[arg(short, long, conflicts_with_all = ["goto", "stdout", "editor"])]
+        find: bool,
+        /// Keep the text exactly as downloaded, page markers and running headers included
+        #[arg(long, visible_alias = "no-clean", conflicts_with = "html")]
+        raw: bool,
+        /// Only RFCs published in or after this year
+        #[arg(long, conflicts_with = "year")]
+        since: Option<u32>,
+        /// Only RFCs published in or before this year
+        #[arg(long, conflicts_with = "year")]
+        until: Option<u32>,
+        /// Only RFCs published in this year
+        #[arg(long)]
+        year: Option<u32>,
+        /// With a date filter, keep entries whose date can't be read from the index
+        #[arg(long)]
+        include_undated: bool,
+        /// Only RFCs with an author whose name contains this text (e.g. "Berners-Lee")
+        #[arg(short, long)]
+        author: Option<String>,
     },
     /// Get a summarized TLDR of an RFC
     Tldr { 
+        #[arg(value_parser = parse_rfc_arg)]
         number: Option<u32>,
-        #[arg(short, long, default_value = "llama-3.1-8b-instant")]
-        model: String
+        /// Model to summarize with [default: llama-3.1-8b-instant on Groq]
+        #[arg(short, long)]
+        model: Option<String>,
+        /// Which service to summarize with [default: groq]
+        #[arg(short, long, value_enum)]
+        provider: Option<Provider>,
+        /// Base URL of the chat-completions API (e.g. http://localhost:8000/v1)
+        #[arg(long)]
+        base_url: Option<String>,
+        /// API key (overrides the provider's env var and the config file)
+        #[arg(long)]
+        api_key: Option<String>,
+        /// Ignore any cached summary and ask the model again
+        #[arg(short, long)]
+        refresh: bool,
+        /// Summarize the whole document in chunks (slower, more complete)
+        #[arg(short, long)]
+        full: bool,
+        /// Pick several RFCs (Tab to mark) and summarize each of them
+        #[arg(long, conflicts_with = "number")]
+        multi: bool,
+        /// Read the system prompt from this file instead of the built-in one
+        #[arg(long, value_name = "FILE")]
+        prompt_file: Option<PathBuf>,
+        /// How detailed the summary should be
+        #[arg(short, long, value_enum)]
+        length: Option<TldrLength>,
+        /// Write the summary in this language (e.g. "es", "de", "Japanese")
+        #[arg(long)]
+        lang: Option<String>,
+        /// Print the tokens the summary used, and its cost if prices are configured
+        #[arg(long)]
+        usage: bool,
+        /// Wrap the summary at this many columns instead of the terminal width
+        #[arg(short, long)]
+        width: Option<usize>,
+        /// How to print the summary
+        #[arg(long, value_enum, default_value = "pretty")]
+        format: TldrFormat,
+        /// Print the model and the exact messages of every request to stderr
+        #[arg(long)]
+        dump_prompt: bool,
+        /// With --dump-prompt, stop before anything is sent to the model
+        #[arg(long, requires = "dump_prompt")]
+        dry_run: bool,
+        /// Keep every line of the answer, including "Here is a summary..." filler
+        #[arg(long)]
+        no_filter: bool,
+        /// Only summarize this section (and its subsections), by number or title (e.g. "5.2", "security")
+        #[arg(long)]
+        section: Option<String>,
+    },
+    /// List the models the summary provider offers, for `tldr --model`
+    Models {
+        /// Which service to ask [default: groq]
+        #[arg(short, long, value_enum)]
+        provider: Option<Provider>,
+        /// Base URL of the chat-completions API (e.g. http://localhost:8000/v1)
+        #[arg(long)]
+        base_url: Option<String>,
+        /// API key (overrides the provider's env var and the config file)
+        #[arg(long)]
+        api_key: Option<String>,
+        /// Ask the provider again instead of reusing the last hour's list
+        #[arg(short, long)]
+        refresh: bool,
+    },
+    /// Show the title, date, status and authors of an RFC
+    Info {
+        #[arg(value_parser = parse_rfc_arg)]
+        number: u32,
+    },
+    /// Print the abstract of an RFC, a quick gist without asking a model
+    Abstract {
+        #[arg(value_parser = parse_rfc_arg)]
+        number: u32,
+    },
+    /// List the authors of an RFC, with affiliation and email where given
+    Authors {
+        #[arg(value_parser = parse_rfc_arg)]
+        number: u32,
+    },
+    /// List the sections of an RFC
+    Toc {
+        #[arg(value_parser = parse_rfc_arg)]
+        number: u32,
+        /// Pick a section with the fuzzy finder and open it in the pager
+        #[arg(short, long)]
+        pick: bool,
+    },
+    /// Open a random RFC
+    Random {
+        /// Only pick among these statuses (e.g. standard,proposed,bcp)
+        #[arg(short, long, value_delimiter = ',')]
+        status: Vec<String>,
+    },
+    /// Find the current RFC(s) replacing an obsoleted one
+    Latest {
+        #[arg(value_parser = parse_rfc_arg)]
+        number: u32,
+        /// Open the newest replacement in the pager
+        #[arg(short, long)]
+        open: bool,
+    },
+    /// Convert an RFC for use elsewhere, e.g. Markdown for a notes app
+    Export {
+        #[arg(value_parser = parse_rfc_arg)]
+        number: u32,
+        #[arg(short, long, value_enum, default_value_t = ExportFormat::Md)]
+        format: ExportFormat,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Print the ABNF grammar rules of an RFC (best effort)
+    Abnf {
+        #[arg(value_parser = parse_rfc_arg)]
+        number: u32,
+        /// Write to this file (e.g. http.abnf) instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Show what changed between two RFCs, e.g. an obsoleted one and its replacement
+    Diff {
+        #[arg(value_parser = parse_rfc_arg)]
+        a: u32,
+        #[arg(value_parser = parse_rfc_arg)]
+        b: u32,
+        /// Show the two texts next to each other instead of a unified diff
+        #[arg(short = 'y', long)]
+        side_by_side: bool,
+        /// Lines of unchanged text around each change
+        #[arg(short = 'U', long, default_value_t = 3)]
+        context: usize,
+    },
+    /// List the reported errata of an RFC
+    Errata {
+        #[arg(value_parser = parse_rfc_arg)]
+        number: u32,
+        /// Hide errata that are not verified (reported, held, rejected)
+        #[arg(long)]
+        verified_only: bool,
+        /// Download the errata again instead of using the cached copy
+        #[arg(short, long)]
+        refresh: bool,
+    },
+    /// Full-text search across all cached RFCs
+    Search {
+        term: String,
+        /// Also download RFCs whose title matches but aren't cached yet
+        #[arg(short, long)]
+        download: bool,
+    },
+    /// Print matching index entries without opening the picker
+    List {
+        /// Only entries whose number or title contains this text
+        #[arg(short, long)]
+        query: Option<String>,
+        /// Only list RFCs with these statuses (e.g. standard,proposed,bcp)
+        #[arg(short, long, value_delimiter = ',')]
+        status: Vec<String>,
+        /// Only RFCs published in or after this year
+        #[arg(long, conflicts_with = "year")]
+        since: Option<u32>,
+        /// Only RFCs published in or before this year
+        #[arg(long, conflicts_with = "year")]
+        until: Option<u32>,
+        /// Only RFCs published in this year
+        #[arg(long)]
+        year: Option<u32>,
+        /// With a date filter, keep entries whose date can't be read from the index
+        #[arg(long)]
+        include_undated: bool,
+        /// Only RFCs with an author whose name contains this text (e.g. "Berners-Lee")
+        #[arg(short, long)]
+        author: Option<String>,
+        /// Stop after this many entries
+        #[arg(short = 'n', long, value_name = "N")]
+        limit: Option<usize>,
+        /// Order entries by this instead of by number
+        #[arg(long, value_enum, default_value = "number")]
+        sort: SortKey,
+        /// Reverse the order (newest first with --sort date)
+        #[arg(long)]
+        reverse: bool,
+    },
+    /// Open an RFC on rfc-editor.org in the default browser
+    Open {
+        #[arg(value_parser = parse_rfc_arg)]
+        number: u32,
+        /// Which rendition to open
+        #[arg(short, long, value_enum, default_value_t = DocFormat::Html)]
+        format: DocFormat,
+    },
+    /// List recently read RFCs, most recent first
+    History {
+        /// How many entries to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+        /// Forget the whole reading history
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Manage a list of bookmarked RFCs
+    Bookmark {
+        #[command(subcommand)]
+        action: BookmarkAction,
+    },
+    /// Show the index age, cache contents and cache location
+    Status,
+    /// Print a shell completion script to stdout
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Remove downloaded RFCs from the local cache
+    ClearCache {
+        /// Also remove the cached RFC index and STD/BCP/FYI indexes
+        #[arg(short, long)]
+        index: bool,
+        /// Remove the whole cache directory
+        #[arg(short, long)]
+        all: bool,
+        /// List what would be removed without deleting anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum BookmarkAction {
+    /// Bookmark an RFC
+    Add {
+        #[arg(value_parser = parse_rfc_arg)]
+        number: u32,
     },
+    /// List bookmarked RFCs
+    List,
+    /// Remove a bookmark
+    Remove {
+        #[arg(value_parser = parse_rfc_arg)]
+        number: u32,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum DocFormat {
+    Txt,
+    Html,
+    Pdf,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum TldrFormat {
+    /// Boxed, colored and wrapped for the terminal
+    Pretty,
+    /// Plain Markdown: a `# RFC N` heading and `-` bullets
+    Md,
+    /// One JSON object with the summary, its model and token usage (what --json prints)
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum SortKey {
+    Number,
+    /// Publication date, undated entries last
+    Date,
+    /// Standing, from Internet Standard down to historic
+    Status,
+    Title,
+}
+
+impl SortKey {
+    fn compare(self, a: &index::RfcEntry, b: &index::RfcEntry) -> std::cmp::Ordering {
+        match self {
+            SortKey::Number => a.number.cmp(&b.number),
+            SortKey::Date => a.published().cmp(&b.published()),
+            SortKey::Status => index::status_rank(&a.status).cmp(&index::status_rank(&b.status)),
+            SortKey::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum TldrLength {
+    Short,
+    Medium,
+    Long,
+}
+
+impl TldrLength {
+    fn name(self) -> &'static str {
+        match self {
+            TldrLength::Short => "short",
+            TldrLength::Medium => "medium",
+            TldrLength::Long => "long",
+        }
+    }
+
+    /// Appended to the system prompt.
+    fn instruction(self) -> &'static str {
+        match self {
+            TldrLength::Short => "Be very brief: a one-sentence pitch and at most 4 bullets.",
+            TldrLength::Medium => "Aim for a short paragraph and about 8 bullets.",
+            TldrLength::Long => "Be thorough: cover every major mechanism, in up to 20 bullets.",
+        }
+    }
+
+    fn max_tokens(self) -> u32 {
+        match self {
+            TldrLength::Short => 250,
+            TldrLength::Medium => 600,
+            TldrLength::Long => 1500,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ExportFormat {
+    /// Markdown with headings and fenced figures
+    Md,
+    /// Plain text without page headers and footers
+    Txt,
+}
+
+impl DocFormat {
+    fn url(self, number: u32) -> String {
+        match self {
+            DocFormat::Txt => format!("https://www.rfc-editor.org/rfc/rfc{}.txt", number),
+            DocFormat::Html => format!("https://www.rfc-editor.org/rfc/rfc{}.html", number),
+            DocFormat::Pdf => format!("https://www.rfc-editor.org/rfc/pdfrfc/rfc{}.txt.pdf", number),
+        }
+    }
+}
+
+/// Set from the global `--json` flag.
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Switch `print_error` (and the CLI's output) to JSON.
+fn set_json_output(on: bool) {
+    JSON_OUTPUT.store(on, Ordering::Relaxed);
+}
+
+/// Whether `--json` is on.
+fn json_output() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Why a run failed, its exit code as the value. 2 is left to clap, which
+/// exits with it on a bad command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Failure {
+    /// A download, file or API request went wrong
+    Error = 1,
+    /// The RFC, draft or index entry asked for doesn't exist, or `random` has nothing to pick
+    NotFound = 3,
+    /// A summary needs an API key and none is configured
+    NoApiKey = 4,
+    /// The picker was left without choosing anything
+    Cancelled = 5,
+}
+
+/// The first failure of the run, 0 while there is none.
+static FAILURE: AtomicU8 = AtomicU8::new(0);
+
+/// Remember that the run failed. The first failure decides the exit code,
+/// the errors it causes further up don't change it.
+fn record_failure(failure: Failure) {
+    let _ = FAILURE.compare_exchange(0, failure as u8, Ordering::Relaxed, Ordering::Relaxed);
+}
+
+/// The code to exit with: 0, or that of the first failure.
+fn exit_code() -> ExitCode {
+    ExitCode::from(FAILURE.load(Ordering::Relaxed))
+}
+
+/// "Error: ..." on stderr, or `{"error": "..."}` on stdout with `--json` so
+/// scripts find it where they expect the output. Counts as a failure.
+fn print_error(message: impl std::fmt::Display) {
+    record_failure(Failure::Error);
+    if json_output() {
+        println!("{}", serde_json::json!({ "error": message.to_string() }));
+    } else {
+        eprintln!("{}: {}", "Error".red(), message);
+    }
+}
+
+/// `print_error` for the library's errors, which also decide the exit code.
+fn report(e: rfcli::Error) {
+    match &e {
+        rfcli::Error::NotFound(_) => record_failure(Failure::NotFound),
+        rfcli::Error::NoApiKey { .. } => record_failure(Failure::NoApiKey),
+        _ => {}
+    }
+    print_error(&e);
+    if let rfcli::Error::NoApiKey { provider, key_env } = &e {
+        let field = if *provider == Provider::Groq { "groq_api_key" } else { "api_key" };
+        eprintln!("Set the {} environment variable, pass --api-key, or add", key_env);
+        eprintln!("  {} = \"...\"", field);
+        match config::config_path() {
+            Some(path) => eprintln!("to {}", path.display()),
+            None => eprintln!("to your rfcli config.toml"),
+        }
+    }
+    if let rfcli::Error::UnknownModel { known, .. } = &e {
+        eprintln!("Available models:");
+        for model in known {
+            eprintln!("  {}", model);
+        }
+    }
+}
+
+/// Read the cached RFC index, downloading it first if needed.
+async fn load_index(force_refresh: bool, offline: bool) -> Option<String> {
+    load_index_file("rfc-index.txt", "RFC index", force_refresh, offline).await
+}
+
+/// `rfcli::load_index_file`, reporting why when there is no index. When the
+/// download fails an older copy beats no index: offer the cached one (just
+/// use it when nobody is there to ask), and only give up without one.
+async fn load_index_file(name: &str, label: &str, force_refresh: bool, offline: bool) -> Option<String> {
+    let e = match rfcli::load_index_file(name, label, force_refresh, offline).await {
+        Ok(content) => return Some(content),
+        Err(e) => e,
+    };
+    let index_path = cache_dir().join(name);
+    if !matches!(e, rfcli::Error::Download(_)) || !index_path.exists() {
+        report(e);
+        return None;
+    }
+    eprintln!("{}: {}", "Warning".yellow(), e);
+    let age = match index_age(&index_path).map(|age| age.as_secs() / (24 * 60 * 60)) {
+        Some(0) => "less than a day old".to_string(),
+        Some(1) => "1 day old".to_string(),
+        Some(days) => format!("{} days old", days),
+        None => "of unknown age".to_string(),
+    };
+    let interactive = std::io::stdin().is_terminal() && std::io::stderr().is_terminal() && !json_output();
+    if interactive {
+        let question = format!("Use the cached {} ({}) instead?", label, age);
+        if !inquire::Confirm::new(&question).with_default(true).prompt().unwrap_or(false) {
+            record_failure(Failure::Error);
+            return None;
+        }
+    } else {
+        eprintln!("{}", format!("Using the cached {} ({}).", label, age).yellow());
+    }
+    read_cached_index(name, label).map_err(report).ok()
 }
 
 #[tokio::main]
-async fn main() {
+async fn main() -> ExitCode {
     let cli = Cli::parse();
+    interrupt::install();
+    run(cli).await;
+    exit_code()
+}
+
+/// Everything `main` does. Failures are recorded as they are reported,
+/// `main` turns the first one into the exit code.
+async fn run(cli: Cli) {
+    if cli.no_color || cli.json || std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) || !std::io::stdout().is_terminal() {
+        colored::control::set_override(false);
+    }
+    set_json_output(cli.json);
+    rfcli::set_report(true);
+    let mut cfg = config::Config::load();
+    let cache_dir_override = cli.cache_dir.clone()
+        .or_else(|| std::env::var_os("RFCLI_CACHE_DIR").filter(|v| !v.is_empty()).map(PathBuf::from));
+    if let Some(dir) = cache_dir_override {
+        cfg.cache_dir = Some(dir);
+    }
+    config::init(cfg);
+    let compressed = compress_plain_cache();
+    if compressed > 0 {
+        eprintln!("{}", format!("Compressed {} cached RFCs.", compressed).dimmed());
+    }
+    let offline = cli.offline || env_flag("RFCLI_OFFLINE");
 
     match cli.command {
         // Step 1: There IS a command
         Some(cmd) => match cmd {
-            Commands::Read { refresh, query } => {
-                // ... your read logic ...
+            Commands::Read {
+                target, refresh, query, pager, status, preview_window, section, goto, bookmarks, no_warn, stats, editor, stdout, latest, refs, prefetch, recent, top, html, tui, raw, find, head,
+                since, until, year, include_undated, author,
+            } => {
+                let stdout = stdout || head.is_some();
+                if stdout {
+                    colored::control::set_override(false);
+                }
+                let pager = pager.or_else(|| config::get().pager.clone());
+                // A target that isn't an RFC, draft or subseries is what the picker searches for
+                let searched = target.as_deref().filter(|t| {
+                    parse_rfc_id(t).is_none() && !t.starts_with("draft-") && index::parse_subseries_id(t).is_none()
+                });
+                let highlight = query.clone().or_else(|| searched.map(String::from));
+                let view = ReadView { section, goto, highlight, pager, stats, no_warn, editor, stdout, head, refs, prefetch, top, html, tui, raw, find };
+
+                // `rfc read 2616` skips the picker entirely
+                if let Some(num) = target.as_deref().and_then(parse_rfc_id) {
+                    read_rfc(num, offline, &view).await;
+                    return;
+                }
+
+                // `rfc read draft-ietf-quic-transport-34` reads an internet-draft
+                if let Some(name) = target.as_deref().filter(|t| t.starts_with("draft-")) {
+                    match fetch_draft(name, latest, offline).await {
+                        Ok((name, content)) => {
+                            let references = (view.refs || view.tui).then(|| referenced_rfcs(&content, None));
+                            // Like `read_rfc`, keep the runtime's other tasks (Ctrl-C included) running meanwhile
+                            let picked = tokio::task::block_in_place(|| {
+                                page_document(&name, content, &view, references.as_deref().unwrap_or_default())
+                            });
+                            let next = picked.or_else(|| {
+                                references.filter(|_| !view.tui).and_then(|numbers| pick_reference(&name, &numbers))
+                            });
+                            if let Some(next) = next {
+                                read_rfc(next, offline, &view).await;
+                            }
+                        }
+                        Err(e) => report(e),
+                    }
+                    return;
+                }
+
+                let mut only: Option<Vec<u32>> = bookmarks
+                    .then(|| bookmarks::load(&bookmarks_path()).iter().map(|b| b.number).collect());
+                let mut target = target;
+
+                // `rfc read std66` opens the RFC behind it, or picks among several
+                if let Some((series, n)) = target.as_deref().and_then(index::parse_subseries_id) {
+                    let Some(numbers) = resolve_subseries(series, n, refresh, offline).await else {
+                        return;
+                    };
+                    if let [num] = numbers[..] {
+                        read_rfc(num, offline, &view).await;
+                        return;
+                    }
+                    only = Some(numbers);
+                    target = None;
+                }
+
+                let mut q = query.or(target);
+
+                // `--recent` only knows the numbers and titles we saved, which is all skim needs
+                if recent && (bookmarks || only.is_none()) {
+                    let entry = |number, title| index::RfcEntry { number, title, ..Default::default() };
+                    let mut picked = false;
+                    loop {
+                        let picks: Vec<index::RfcEntry> = if bookmarks {
+                            bookmarks::load(&bookmarks_path()).into_iter().map(|b| entry(b.number, b.title)).collect()
+                        } else {
+                            history::recent(&history_path()).into_iter().map(|h| entry(h.number, h.title)).collect()
+                        };
+                        if picks.is_empty() {
+                            let what = if bookmarks { "No bookmarks" } else { "No reading history" };
+                            eprintln!("{}", format!("{} yet, showing the full index.", what).yellow());
+                            break;
+                        }
+                        let Some(num) = run_picker(picker_items(picks), q.take(), preview_window.as_deref(), false).and_then(|n| n.first().copied()) else {
+                            if !picked {
+                                record_failure(Failure::Cancelled);
+                            }
+                            return;
+                        };
+                        picked = true;
+                        read_rfc(num, offline, &view).await;
+                    }
+                }
+
+                let filter = EntryFilter { status, dates: year_range(since, until, year, include_undated), author };
                 let mut first_run = refresh;
-                let mut q = query;
-                loop {
-                    if let Some(num) = fuzzy_select_rfc(first_run, q.take()) {
-                        first_run = false;
-                        if let Ok(content) = fetch_rfc(num).await {
-                            view_in_pager(&content);
+                let mut picked = false;
+                while let Some(num) = fuzzy_select_rfc(first_run, q.take(), offline, &filter, preview_window.as_deref(), only.as_deref()).await {
+                    first_run = false;
+                    picked = true;
+                    read_rfc(num, offline, &view).await;
+                }
+                // Leaving the picker after reading something is how reading ends
+                if !picked {
+                    record_failure(Failure::Cancelled);
+                }
+            }
+            Commands::Tldr {
+                number, model, provider, base_url, api_key, refresh, full, multi, prompt_file, length, lang, usage, width, format, dump_prompt, dry_run, no_filter, section,
+            } => {
+                if number.is_none() && !multi {
+                    interactive_mode(offline).await;
+                    return;
+                }
+                let Some(endpoint) = Endpoint::resolve(provider, base_url, model, api_key).map_err(report).ok() else {
+                    return;
+                };
+                let Some(options) = TldrOptions::resolve(full, prompt_file.as_deref(), length, lang, usage, width, !no_filter) else {
+                    return;
+                };
+                let format = if json_output() { TldrFormat::Json } else { format };
+                let options = TldrOptions { format, dump_prompt, dry_run, section, ..options };
+                if format != TldrFormat::Pretty {
+                    colored::control::set_override(false);
+                }
+                let numbers = match number {
+                    Some(n) => vec![n],
+                    None => match fuzzy_select_rfcs(false, None, offline, &EntryFilter::default(), None, None, true).await {
+                        Some(numbers) => numbers,
+                        None => {
+                            record_failure(Failure::Cancelled);
+                            return;
                         }
-                    } else { break; }
+                    },
+                };
+                for (i, n) in numbers.iter().enumerate() {
+                    if numbers.len() > 1 && format == TldrFormat::Pretty {
+                        println!("\n{}", format!("━━━ [{}/{}] RFC {} ━━━", i + 1, numbers.len(), n).dimmed());
+                    }
+                    if !summarize(*n, &endpoint, &options, refresh, offline).await {
+                        return;
+                    }
+                }
+            }
+            Commands::Models { provider, base_url, api_key, refresh } => {
+                // The configured model may be the very one being looked for, don't insist on it
+                let model = provider.or(config::get().provider).unwrap_or_default().default_model().to_string();
+                let Some(endpoint) = Endpoint::resolve(provider, base_url, Some(model), api_key).map_err(report).ok() else {
+                    return;
+                };
+                list_models(&endpoint, refresh).await;
+            }
+            Commands::Info { number } => {
+                print_info(number, offline).await;
+            }
+            Commands::Abstract { number } => {
+                print_abstract(number, offline).await;
+            }
+            Commands::Authors { number } => {
+                print_authors(number, offline).await;
+            }
+            Commands::Abnf { number, output } => {
+                match fetch_rfc(number, offline).await {
+                    Ok(content) => export_abnf(number, &content, output.as_deref()),
+                    Err(e) => report(e),
                 }
             }
-            Commands::Tldr { number, model } => {
-                match number {
-                    Some(n) => {
-                        if let Ok(content) = fetch_rfc(n).await {
-                            generate_tldr(n, &content, &model).await;
+            Commands::Toc { number, pick } => {
+                match fetch_rfc(number, offline).await {
+                    Ok(content) => show_toc(&content, pick),
+                    Err(e) => report(e),
+                }
+            }
+            Commands::List { query, status, since, until, year, include_undated, author, limit, sort, reverse } => {
+                let filter = EntryFilter { status, dates: year_range(since, until, year, include_undated), author };
+                list_entries(query.as_deref(), &filter, sort, reverse, limit, offline).await;
+            }
+            Commands::Open { number, format } => {
+                open_in_browser(&format.url(number));
+            }
+            Commands::Latest { number, open } => {
+                let Some(newest) = print_latest(number, offline).await else { return };
+                if open {
+                    match fetch_rfc(newest, offline).await {
+                        Ok(content) => {
+                            record_read(newest);
+                            view_in_pager(&clean_rfc_text(&content), config::get().pager.as_deref(), None);
                         }
+                        Err(e) => report(e),
+                    }
+                }
+            }
+            Commands::Export { number, format, output } => {
+                match fetch_rfc(number, offline).await {
+                    Ok(content) => export_rfc(number, &content, format, output.as_deref()),
+                    Err(e) => report(e),
+                }
+            }
+            Commands::Diff { a, b, side_by_side, context } => {
+                diff_rfcs(a, b, side_by_side, context, offline).await;
+            }
+            Commands::Errata { number, verified_only, refresh } => {
+                match fetch_errata(number, refresh, offline).await {
+                    Ok(errata) => print_errata(number, &errata, verified_only),
+                    Err(e) => report(e),
+                }
+            }
+            Commands::Random { status } => {
+                let Some(num) = random_rfc(&status, offline).await else { return };
+                match fetch_rfc(num, offline).await {
+                    Ok(content) => {
+                        record_read(num);
+                        view_in_pager(&clean_rfc_text(&content), config::get().pager.as_deref(), None);
                     }
-                    None => interactive_mode().await,
+                    Err(e) => report(e),
                 }
             }
+            Commands::Search { term, download } => {
+                search_cached(&term, download, offline).await;
+            }
+            Commands::History { limit, clear } => {
+                show_history(limit, clear);
+            }
+            Commands::Bookmark { action } => {
+                manage_bookmarks(action, offline).await;
+            }
+            Commands::Status => {
+                print_status();
+            }
+            Commands::Completions { shell } => {
+                clap_complete::generate(shell, &mut Cli::command(), env!("CARGO_BIN_NAME"), &mut std::io::stdout());
+            }
+            Commands::ClearCache { index, all, dry_run } => {
+                clear_cache(index, all, dry_run);
+            }
         },
         // Step 2: There is NO command (user just ran `rfcli`)
         None => {
-            interactive_mode().await;
+            interactive_mode(offline).await;
         }
     }
 }
 
-async fn interactive_mode() {
-    let _initial_run = true;
-    
-    loop {
-        // 1. Search for an RFC
-        if let Some(rfc_num) = fuzzy_select_rfc(false, None) {
-            
-            // 2. Ask: Read or TLDR?
-            let options = vec!["Read Full RFC", "Get AI Summary", "Exit"];
-            let ans = Select::new("What would you like to do?", options).prompt();
-
-            match ans {
-                Ok("Read Full RFC") => {
-                    if let Ok(content) = fetch_rfc(rfc_num).await {
-                        view_in_pager(&content);
+/// `models`: the provider's model IDs, the one `tldr` uses by default marked.
+async fn list_models(endpoint: &Endpoint, refresh: bool) {
+    let cached = provider::cached_models(&endpoint.base_url).filter(|(_, age)| !refresh && *age < provider::MODELS_TTL);
+    let models = match cached {
+        Some((models, _)) => models,
+        None => {
+            if let Err(e) = endpoint.require_api_key() {
+                report(e);
+                return;
+            }
+            let listed = match net::client() {
+                Ok(client) => endpoint.list_models(client).await,
+                Err(e) => Err(e),
+            };
+            match listed {
+                Ok(models) => {
+                    if let Err(e) = provider::save_models(&endpoint.base_url, &models) {
+                        eprintln!("{}: could not cache the model list: {}", "Warning".yellow(), e);
                     }
+                    models
                 }
-                Ok("Get AI Summary") => {
-                    if let Ok(content) = fetch_rfc(rfc_num).await {
-                        // Use your default cloud model
-                        generate_tldr(rfc_num, &content, "llama-3.1-8b-instant").await;
-                    }
+                Err(e) => {
+                    report(e);
+                    return;
                 }
-                _ => break, // Exit or Error
             }
+        }
+    };
 
-            // 3. Ask if they want to search again
-            let again = Select::new("Done!", vec!["Search Again", "Exit"]).prompt();
-            if let Ok("Exit") = again { break; }
-            
+    if json_output() {
+        print_json(&models);
+        return;
+    }
+    let default = config::get().default_model.clone().unwrap_or_else(|| endpoint.provider.default_model().to_string());
+    for model in &models {
+        if *model == default {
+            println!("{} {}", model.bold(), "(default)".dimmed());
         } else {
-            break; // User hit Esc in fuzzy search
+            println!("{}", model);
+        }
+    }
+}
+
+/// Pretty-printed JSON on stdout, for `--json`.
+fn print_json(value: &impl serde::Serialize) {
+    match serde_json::to_string_pretty(value) {
+        Ok(out) => println!("{}", out),
+        Err(e) => print_error(e),
+    }
+}
+
+/// Parse "2616", "RFC2616", "rfc 2616" and friends into an RFC number.
+fn parse_rfc_id(s: &str) -> Option<u32> {
+    let s = s.trim();
+    let digits = match s.get(..3) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("rfc") => s[3..].trim_start(),
+        _ => s,
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// clap adapter for `parse_rfc_id`.
+fn parse_rfc_arg(s: &str) -> Result<u32, String> {
+    parse_rfc_id(s).ok_or_else(|| format!("'{}' is not an RFC number (try 2616 or RFC2616)", s))
+}
+
+/// True when the environment variable is set to something other than "0"/"false".
+fn env_flag(name: &str) -> bool {
+    std::env::var(name)
+        .map(|v| !v.is_empty() && v != "0" && !v.eq_ignore_ascii_case("false"))
+        .unwrap_or(false)
+}
+
+async fn interactive_mode(offline: bool) {
+    // 1. Search for an RFC (Esc in the fuzzy search ends the loop)
+    while let Some(rfc_num) = fuzzy_select_rfc(false, None, offline, &EntryFilter::default(), None, None).await {
+        // 2. Ask: Read or TLDR?
+        let options = vec!["Read Full RFC", "Get AI Summary", "Exit"];
+        let ans = Select::new("What would you like to do?", options).prompt();
+
+        match ans {
+            Ok("Read Full RFC") => {
+                match fetch_rfc(rfc_num, offline).await {
+                    Ok(content) => {
+                        record_read(rfc_num);
+                        warn_if_obsoleted(rfc_num);
+                        view_in_pager(&clean_rfc_text(&content), config::get().pager.as_deref(), None);
+                    }
+                    Err(e) => report(e),
+                }
+            }
+            Ok("Get AI Summary") => {
+                // Use your default cloud model
+                let Some(endpoint) = Endpoint::resolve(None, None, None, None).map_err(report).ok() else { continue };
+                let Some(options) = TldrOptions::resolve(false, None, None, None, false, None, true) else { continue };
+                if !summarize(rfc_num, &endpoint, &options, false, offline).await {
+                    continue;
+                }
+            }
+            _ => break, // Exit or Error
         }
+
+        // 3. Ask if they want to search again
+        let again = Select::new("Done!", vec!["Search Again", "Exit"]).prompt();
+        if let Ok("Exit") = again { break; }
+    }
+}
+
+/// Lines of the model's own chatter a summary leaves out unless `tldr_filler`
+/// says otherwise: an intro leading into the bullets, or a bare heading.
+const TLDR_FILLER: &[&str] = &[r"here(?: is|'s| are)\b.*:", r"#*\s*(?:tl;?dr|summary)(?: of rfc ?\d+)?:?"];
+
+/// How a summary is produced, shared by the single, `--multi` and interactive paths.
+struct TldrOptions {
+    /// Summarize the whole document in chunks
+    full: bool,
+    /// System prompt for the final summary
+    system_prompt: String,
+    /// Requested summary size, None keeps the model's own judgement
+    length: Option<TldrLength>,
+    /// Language to answer in, None for English
+    lang: Option<String>,
+    /// Report token usage after a summary
+    usage: bool,
+    /// `--width`, None sizes the summary to the terminal
+    width: Option<usize>,
+    format: TldrFormat,
+    /// Show every request on stderr before sending it
+    dump_prompt: bool,
+    /// Stop short of sending anything
+    dry_run: bool,
+    /// Whole-line patterns of filler to drop from the answer
+    filler: Vec<Regex>,
+    /// `--section`: summarize only the section this finds
+    section: Option<String>,
+}
+
+impl TldrOptions {
+    /// `--prompt-file` wins over `tldr_system_prompt` in the config, which wins over the built-in prompt.
+    fn resolve(
+        full: bool,
+        prompt_file: Option<&std::path::Path>,
+        length: Option<TldrLength>,
+        lang: Option<String>,
+        usage: bool,
+        width: Option<usize>,
+        filter: bool,
+    ) -> Option<TldrOptions> {
+        let system_prompt = match prompt_file {
+            Some(path) => match fs::read_to_string(path) {
+                Ok(prompt) if !prompt.trim().is_empty() => prompt.trim().to_string(),
+                Ok(_) => {
+                    print_error(format!("{} is empty", path.display()));
+                    return None;
+                }
+                Err(e) => {
+                    print_error(format!("{}: {}", path.display(), e));
+                    return None;
+                }
+            },
+            None => config::get().tldr_system_prompt.clone().unwrap_or_else(|| TLDR_SYSTEM_PROMPT.to_string()),
+        };
+        let lang = lang.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+        let filler = if filter { filler_patterns() } else { Vec::new() };
+        Some(TldrOptions {
+            full,
+            system_prompt,
+            length,
+            lang,
+            usage,
+            width,
+            format: TldrFormat::Pretty,
+            dump_prompt: false,
+            dry_run: false,
+            filler,
+            section: None,
+        })
     }
 }
 
-async fn generate_tldr(number: u32, text: &str, _model: &str) {
-    let api_key = std::env::var("GROQ_API_KEY")
-        .expect("Please set the GROQ_API_KEY environment variable");
+/// `tldr_filler` from the config (or the built-in list), compiled to match
+/// whole lines ignoring case. Broken patterns are skipped with a warning.
+fn filler_patterns() -> Vec<Regex> {
+    let configured = config::get().tldr_filler.as_ref();
+    let patterns: Vec<&str> = match configured {
+        Some(patterns) => patterns.iter().map(String::as_str).collect(),
+        None => TLDR_FILLER.to_vec(),
+    };
+    patterns
+        .into_iter()
+        .filter_map(|pattern| match Regex::new(&format!("(?i)^(?:{})$", pattern)) {
+            Ok(re) => Some(re),
+            Err(e) => {
+                eprintln!("{}: ignoring tldr_filler pattern '{}': {}", "Warning".yellow(), pattern, e);
+                None
+            }
+        })
+        .collect()
+}
 
-    let cleaned_text = clean_rfc_text(text);
-    let context = cleaned_text.lines().take(300).collect::<Vec<_>>().join("\n");
+/// Print the cached summary or generate a new one. False when that needs an API key we don't have.
+async fn summarize(number: u32, endpoint: &Endpoint, options: &TldrOptions, refresh: bool, offline: bool) -> bool {
+    // The point of --dump-prompt is seeing the request, a cached answer would skip it
+    if !refresh && !options.dump_prompt && show_cached_tldr(number, &endpoint.model, options) {
+        if options.usage && options.format != TldrFormat::Json {
+            println!("{}", "Tokens: none, this summary came from the cache".dimmed());
+        }
+        return true;
+    }
+    if let (false, Err(e)) = (options.dry_run, endpoint.require_api_key()) {
+        report(e);
+        return false;
+    }
+    match fetch_rfc(number, offline).await {
+        Ok(content) => generate_tldr(number, &content, endpoint, options).await,
+        Err(e) => report(e),
+    }
+    true
+}
 
+async fn generate_tldr(number: u32, text: &str, endpoint: &Endpoint, options: &TldrOptions) {
+    let full = options.full;
+    let mut system_prompt = options.system_prompt.clone();
+    if let Some(length) = options.length {
+        system_prompt.push(' ');
+        system_prompt.push_str(length.instruction());
+    }
+    if let Some(lang) = &options.lang {
+        system_prompt.push_str(&format!(" Write the entire summary in this language: {}.", lang));
+    }
+    let (subject, context) = match &options.section {
+        Some(query) => {
+            let cleaned = clean_rfc_text(text);
+            let sections = sections::parse_sections(&cleaned);
+            let Some(section) = sections::find_section(&sections, query) else {
+                record_failure(Failure::NotFound);
+                print_error(format!("RFC {} has no section matching '{}'", number, query));
+                return;
+            };
+            let subject = format!("section {} (\"{}\") of RFC {}", section.number, section.title, number);
+            (subject, sections::section_text(&cleaned, section))
+        }
+        None => (format!("RFC {}", number), tldr_context(text)),
+    };
     let pb = ProgressBar::new_spinner();
+    let template = if full { "{spinner:.magenta} {msg} [{pos}/{len}]" } else { "{spinner:.magenta} {msg}" };
     pb.set_style(ProgressStyle::default_spinner()
         .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"])
-        .template("{spinner:.magenta} {msg}")
+        .template(template)
         .unwrap());
-    pb.set_message("Querying Groq Cloud...");
+    pb.set_message(format!("Querying {}...", endpoint.provider.name()));
     pb.enable_steady_tick(std::time::Duration::from_millis(120));
+    track_progress(&pb);
 
-    let client = reqwest::Client::new();
-    let res = client
-        .post("https://api.groq.com/openai/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .json(&json!({
-            "model": "llama-3.1-8b-instant",
-            "messages": [
-                {
-                    "role": "system",
-                    "content": "You are a Senior Systems Engineer. Summarize the RFC for a terminal UI. DO NOT use Markdown bolding (no asterisks). Use a simple 'TITLE: description' format for bullets. Keep the elevator pitch at the top."
-                },
-                {
-                    "role": "user",
-                    "content": format!("Summarize RFC {}:\n\n{}", number, context)
+    let request = SummaryRequest {
+        subject: &subject,
+        context: &context,
+        system_prompt: &system_prompt,
+        full,
+        max_tokens: options.length.map(TldrLength::max_tokens),
+        include_usage: options.usage || options.format == TldrFormat::Json,
+    };
+    let mut printer: Option<TldrPrinter> = None;
+    let summary = summarize_with(
+        endpoint,
+        &request,
+        |pending| {
+            match pending.part {
+                Some((part, parts)) => {
+                    pb.set_length(parts as u64 + 1);
+                    pb.set_position(part as u64);
+                    pb.set_message(format!("Summarizing part {} of {}...", part + 1, parts));
                 }
-            ]
-        }))
-        .send()
-        .await;
-
+                None if full => {
+                    pb.set_position(pb.length().unwrap_or(1) - 1);
+                    pb.set_message("Combining summaries...");
+                }
+                None => {}
+            }
+            if options.dump_prompt {
+                pb.suspend(|| dump_prompt(endpoint, pending.system, pending.user, pending.max_tokens));
+            }
+            !options.dry_run
+        },
+        |token| {
+            // As JSON the summary is printed whole once it's complete
+            if options.format == TldrFormat::Json {
+                return;
+            }
+            printer
+                .get_or_insert_with(|| {
+                    pb.finish_and_clear();
+                    TldrPrinter::new(number, false, options)
+                })
+                .push(token);
+        },
+    )
+    .await;
     pb.finish_and_clear();
+    if let Some(printer) = printer {
+        printer.finish();
+    }
 
-    match res {
-        Ok(response) => {
-            let body = response.text().await.unwrap_or_default();
-            let v: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
-            
-            // The 'if let' block now contains all the printing logic to keep 'summary' in scope
-            if let Some(summary_text) = v["choices"][0]["message"]["content"].as_str() {
-                // 1. Detect terminal width (defaults to 80 if it can't detect)
-                let term_width = termsize::get().map(|t| t.cols as usize).unwrap_or(80);
-                // 2. Set wrapping options (leaving a little margin for our box/indent)
-                let wrap_options = Options::new(term_width - 6);
-
-                println!("\n{}", "╭──────────────────────────────────────────────────────────╮".cyan().bold());
-                println!("  {} {} {}", "🚀".bold(), "RFC".bold(), number.to_string().bold().yellow());
-                println!("{}", "╰──────────────────────────────────────────────────────────╯".cyan().bold());
-                
-                for line in summary_text.lines() {
-                    let trimmed = line.trim();
-                    if trimmed.is_empty() { continue; }
-
-                    // Skip conversational filler from the AI
-                    let lower = trimmed.to_lowercase();
-                    if lower.starts_with("here is") || lower.contains("summary of rfc") {
-                        continue;
-                    }
+    match summary {
+        Ok(Some(reply)) => {
+            // Save for next time, a failed write just means we ask again later
+            let cache_path = tldr_cache_path(number, &endpoint.model, options);
+            if let Some(parent) = cache_path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            let _ = write_atomic(&cache_path, &reply.text);
+            if options.format == TldrFormat::Json {
+                print_tldr_json(number, &reply.text, &endpoint.model, false, reply.usage, options);
+            } else if options.usage {
+                print_usage(reply.usage, endpoint.provider);
+            }
+        }
+        Ok(None) if full => {
+            eprintln!("{}", "Dry run: the final request is built from the answers to these, nothing was sent.".dimmed());
+        }
+        Ok(None) => eprintln!("{}", "Dry run: nothing was sent.".dimmed()),
+        Err(e) => report(e),
+    }
+}
 
-                    // Clean and print with high contrast for the X220 screen
-                    let clean_line = trimmed.replace("**", "");
-                    // 3. Wrap the cleaned line
-                    let wrapped_lines = wrap(&clean_line, &wrap_options);
+/// `tldr --dump-prompt`: the messages of one request, exactly as they are sent.
+fn dump_prompt(endpoint: &Endpoint, system: &str, user: &str, max_tokens: Option<u32>) {
+    let limit = max_tokens.map(|n| format!(", max_tokens {}", n)).unwrap_or_default();
+    eprintln!("{}", format!("━━━ {}{} ━━━", endpoint.describe(), limit).dimmed());
+    eprintln!("{}\n{}\n", "[system]".cyan().bold(), system);
+    eprintln!("{}\n{}\n", "[user]".cyan().bold(), user);
+}
 
-                    for (i, wrapped) in wrapped_lines.iter().enumerate() {
-                        if i == 0 && (clean_line.starts_with('*') || clean_line.starts_with('-')) {
-                            // First line of a bullet point gets the bullet
-                            println!("  {} {}", "•".cyan().bold(), wrapped[1..].trim().white().bold());
-                        } else {
-                            // Subsequent wrapped lines are indented to match
-                            println!("    {}", wrapped.white().bold());
-                        }
-                    }
-                }
+/// Summaries are cached per model, e.g. `tldr/rfc2616-llama-3.1-8b-instant.txt`
+/// (with `-full` for whole-document summaries, `-short`/`-medium`/`-long` for `--length`
+/// and the language for `--lang`).
+fn tldr_cache_path(number: u32, model: &str, options: &TldrOptions) -> PathBuf {
+    let model = model.replace(['/', '\\', ':'], "_");
+    let mut suffix = String::new();
+    if options.full {
+        suffix.push_str("-full");
+    }
+    if let Some(length) = options.length {
+        suffix.push('-');
+        suffix.push_str(length.name());
+    }
+    if let Some(lang) = &options.lang {
+        suffix.push('-');
+        suffix.push_str(&lang.to_lowercase().replace(['/', '\\', ':', ' '], "_"));
+    }
+    if let Some(section) = &options.section {
+        suffix.push_str("-section-");
+        suffix.push_str(&section.trim().to_lowercase().replace(['/', '\\', ':', ' '], "_"));
+    }
+    cache_dir().join("tldr").join(format!("rfc{}-{}{}.txt", number, model, suffix))
+}
+
+/// Print a previously generated summary if there is one.
+fn show_cached_tldr(number: u32, model: &str, options: &TldrOptions) -> bool {
+    match fs::read_to_string(tldr_cache_path(number, model, options)) {
+        Ok(summary) if !summary.trim().is_empty() => {
+            if options.format == TldrFormat::Json {
+                print_tldr_json(number, &summary, model, true, None, options);
             } else {
-                eprintln!("{}: API response did not contain a summary.", "Error".red());
-                println!("Debug: {}", body);
+                print_tldr(number, &summary, true, options);
             }
+            true
         }
-        Err(e) => eprintln!("{}: {}", "Network Error".red(), e),
+        _ => false,
     }
 }
 
-// --- Logic Functions ---
-
-async fn fetch_rfc(number: u32) -> Result<String, Box<dyn std::error::Error>> {
-    let cache_path = dirs::cache_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("rfcli")
-        .join(format!("rfc{}.txt", number));
+/// `tldr --json`: the summary with what it's about and what it cost, usage
+/// stays null when it came from the cache or the server didn't report it.
+fn print_tldr_json(number: u32, summary: &str, model: &str, cached: bool, usage: Option<Usage>, options: &TldrOptions) {
+    print_json(&serde_json::json!({
+        "number": number,
+        "title": cached_index_entry(number).map(|entry| entry.title),
+        "section": options.section,
+        "model": model,
+        "cached": cached,
+        "summary": summary.trim(),
+        "usage": usage,
+    }));
+}
 
-    // If it's in the cache, read it!
-    if cache_path.exists() {
-        return Ok(fs::read_to_string(cache_path)?);
+/// `tldr --usage`: token counts, and a cost estimate when the config has prices.
+fn print_usage(usage: Option<Usage>, provider: Provider) {
+    let Some(usage) = usage else {
+        println!("{}", format!("Tokens: not reported by {}", provider.name()).dimmed());
+        return;
+    };
+    let mut line = format!(
+        "Tokens: {} prompt + {} completion = {}",
+        group_thousands(usage.prompt_tokens as usize),
+        group_thousands(usage.completion_tokens as usize),
+        group_thousands(usage.total() as usize)
+    );
+    let cfg = config::get();
+    if cfg.prompt_price_per_million.is_some() || cfg.completion_price_per_million.is_some() {
+        let cost = usage.prompt_tokens as f64 * cfg.prompt_price_per_million.unwrap_or(0.0)
+            + usage.completion_tokens as f64 * cfg.completion_price_per_million.unwrap_or(0.0);
+        line.push_str(&format!(" (≈ ${:.4})", cost / 1_000_000.0));
     }
-
-    // Otherwise, fetch and save it
-    let url = format!("https://www.rfc-editor.org/rfc/rfc{}.txt", number);
-    let content = reqwest::get(url).await?.text().await?;
-    
-    // Save for next time
-    let _ = fs::write(cache_path, &content);
-    
-    Ok(content)
+    println!("{}", line.dimmed());
 }
 
-fn clean_rfc_text(raw_text: &str) -> String {
-    let no_feeds = raw_text.replace('\x0C', "");
-    let header_footer_re = Regex::new(r"(?m)^.*\[Page \d+\].*$|^RFC \d+.*$").unwrap();
-    let cleaned = header_footer_re.replace_all(&no_feeds, "");
-    let multi_space_re = Regex::new(r"\n{3,}").unwrap();
-    multi_space_re.replace_all(&cleaned, "\n\n").to_string()
+fn print_tldr(number: u32, summary_text: &str, cached: bool, options: &TldrOptions) {
+    let mut printer = TldrPrinter::new(number, cached, options);
+    printer.push(summary_text);
+    printer.finish();
 }
 
-fn fuzzy_select_rfc(force_refresh: bool, query: Option<String>) -> Option<u32> {
-    let cache_dir = dirs::cache_dir()?.join("rfcli");
-    let index_path = cache_dir.join("rfc-index.txt");
+/// Narrowest the summary gets, whatever the terminal claims (tmux splits can misreport).
+const TLDR_MIN_WIDTH: usize = 40;
+/// Widest the summary gets without an explicit `--width`.
+const TLDR_MAX_WIDTH: usize = 120;
+/// Width used when stdout isn't a terminal.
+const TLDR_PIPE_WIDTH: usize = 80;
 
-    if !cache_dir.exists() {
-        fs::create_dir_all(&cache_dir).ok()?;
+/// Columns to lay the summary out in: `--width` if given, otherwise the
+/// terminal width kept between TLDR_MIN_WIDTH and TLDR_MAX_WIDTH.
+fn tldr_width(width: Option<usize>) -> usize {
+    if let Some(width) = width {
+        return width.max(TLDR_MIN_WIDTH);
     }
-
-    // Download if it doesn't exist OR if user passed the -r flag
-    if !index_path.exists() || force_refresh {
-        println!("{}", "Updating RFC index from IETF...".yellow());
-        let response = reqwest::blocking::get("https://www.rfc-editor.org/rfc/rfc-index.txt").ok()?;
-        let content = response.text().ok()?;
-        fs::write(&index_path, content).ok()?;
-        println!("{}", "Index updated successfully.".green());
+    if !std::io::stdout().is_terminal() {
+        return TLDR_PIPE_WIDTH;
     }
+    termsize::get()
+        .map(|t| t.cols as usize)
+        .unwrap_or(TLDR_PIPE_WIDTH)
+        .clamp(TLDR_MIN_WIDTH, TLDR_MAX_WIDTH)
+}
 
-    let index_data = fs::read_to_string(index_path).ok()?;
-    
-    let filtered_index: String = index_data.lines()
-        .filter(|line| line.trim().chars().next().map(|c| c.is_ascii_digit()).unwrap_or(false))
-        .collect::<Vec<_>>()
-        .join("\n");
+/// Renders a summary into the boxed layout line by line, so it can be fed
+/// streamed tokens just as well as a whole cached summary.
+struct TldrPrinter {
+    wrap_options: Options<'static>,
+    pending: String,
+    /// `--format md`: plain Markdown, no box, colors or wrapping
+    markdown: bool,
+    /// Markdown only: whether the last line printed was a bullet
+    in_list: bool,
+    filler: Vec<Regex>,
+}
 
-    let item_reader = SkimItemReader::default();
-    let items = item_reader.of_bufread(Cursor::new(filtered_index));
+impl TldrPrinter {
+    fn new(number: u32, cached: bool, options: &TldrOptions) -> TldrPrinter {
+        // Set wrapping options (leaving a little margin for our box/indent)
+        let wrap_options = Options::new(tldr_width(options.width) - 6);
 
-    let mut options_builder = SkimOptionsBuilder::default();
-    options_builder
-        .height(Some("50%"))
-        .multi(false)
-        .bind(vec!["esc:abort", "ctrl-c:abort"]);
+        let label = match &options.section {
+            Some(section) => format!("{} § {}", number, section.trim()),
+            None => number.to_string(),
+        };
+        if options.format == TldrFormat::Md {
+            println!("# RFC {}", label);
+            return TldrPrinter { wrap_options, pending: String::new(), markdown: true, in_list: false, filler: options.filler.clone() };
+        }
 
-    // If a query was provided, set it as the initial search text
+        println!("\n{}", "╭──────────────────────────────────────────────────────────╮".cyan().bold());
+        if cached {
+            println!("  {} {} {} {}", "🚀".bold(), "RFC".bold(), label.bold().yellow(), "(cached)".dimmed());
+        } else {
+            println!("  {} {} {}", "🚀".bold(), "RFC".bold(), label.bold().yellow());
+        }
+        println!("{}", "╰──────────────────────────────────────────────────────────╯".cyan().bold());
+
+        TldrPrinter { wrap_options, pending: String::new(), markdown: false, in_list: false, filler: options.filler.clone() }
+    }
+
+    /// Add text, printing every line that is now complete.
+    fn push(&mut self, text: &str) {
+        self.pending.push_str(text);
+        while let Some(pos) = self.pending.find('\n') {
+            let line: String = self.pending.drain(..=pos).collect();
+            self.print_line(&line);
+        }
+    }
+
+    fn finish(mut self) {
+        let rest = std::mem::take(&mut self.pending);
+        self.print_line(&rest);
+        if self.markdown {
+            // Keeps `--multi` summaries apart
+            println!();
+        }
+    }
+
+    fn print_line(&mut self, line: &str) {
+        let trimmed = line.trim();
+        if trimmed.is_empty() { return; }
+
+        // Clean and print with high contrast for the X220 screen
+        let clean_line = trimmed.replace("**", "");
+        // Skip conversational filler from the AI. Patterns match whole lines, so a
+        // real bullet that happens to mention "summary of RFC" survives.
+        if self.filler.iter().any(|re| re.is_match(&clean_line)) {
+            return;
+        }
+        if self.markdown {
+            self.print_markdown_line(&clean_line);
+            return;
+        }
+        // 3. Wrap the cleaned line
+        let wrapped_lines = wrap(&clean_line, &self.wrap_options);
+
+        for (i, wrapped) in wrapped_lines.iter().enumerate() {
+            if i == 0 && (clean_line.starts_with('*') || clean_line.starts_with('-')) {
+                // First line of a bullet point gets the bullet
+                println!("  {} {}", "•".cyan().bold(), wrapped[1..].trim().white().bold());
+            } else {
+                // Subsequent wrapped lines are indented to match
+                println!("    {}", wrapped.white().bold());
+            }
+        }
+    }
+
+    /// Bullets become `- ` items, anything else a paragraph, with a blank line between blocks.
+    fn print_markdown_line(&mut self, line: &str) {
+        let item = line.strip_prefix(['*', '-', '•']).map(str::trim);
+        let is_item = item.is_some();
+        // Consecutive bullets form one list, every paragraph stands alone
+        if !(is_item && self.in_list) {
+            println!();
+        }
+        match item {
+            Some(item) => println!("- {}", item),
+            None => println!("{}", line),
+        }
+        self.in_list = is_item;
+    }
+}
+
+// --- Logic Functions ---
+
+fn print_errata(number: u32, errata: &[serde_json::Value], verified_only: bool) {
+    let field = |erratum: &serde_json::Value, key: &str| -> String {
+        match &erratum[key] {
+            serde_json::Value::String(s) => s.trim().to_string(),
+            serde_json::Value::Number(n) => n.to_string(),
+            _ => String::new(),
+        }
+    };
+
+    let shown: Vec<&serde_json::Value> = errata.iter()
+        .filter(|erratum| !verified_only || field(erratum, "errata_status_code").eq_ignore_ascii_case("verified"))
+        .collect();
+    if shown.is_empty() {
+        let kind = if verified_only { "verified errata" } else { "errata" };
+        println!("{}", format!("No {} for RFC {}.", kind, number).green());
+        return;
+    }
+
+    for erratum in shown {
+        let status = field(erratum, "errata_status_code");
+        let status = match status.to_ascii_lowercase().as_str() {
+            "verified" => status.green(),
+            "rejected" => status.red(),
+            _ => status.yellow(),
+        };
+        println!(
+            "{} {}  [{}, {}]  {}",
+            "Erratum".bold(),
+            field(erratum, "errata_id").bold().yellow(),
+            field(erratum, "errata_type_code"),
+            status,
+            field(erratum, "section").dimmed()
+        );
+        for (label, key) in [("Original:", "orig_text"), ("Corrected:", "correct_text"), ("Notes:", "notes")] {
+            let text = field(erratum, key);
+            if text.is_empty() {
+                continue;
+            }
+            println!("  {}", label.cyan());
+            for line in text.lines() {
+                println!("    {}", line.trim_end());
+            }
+        }
+        println!();
+    }
+}
+
+/// How `read` presents a document.
+struct ReadView {
+    section: Option<String>,
+    goto: Option<String>,
+    /// The picker's search, highlighted (and opened at) in the document
+    highlight: Option<String>,
+    pager: Option<String>,
+    stats: bool,
+    no_warn: bool,
+    editor: bool,
+    stdout: bool,
+    /// With `stdout`, stop after this many lines
+    head: Option<usize>,
+    /// Offer the referenced RFCs after reading
+    refs: bool,
+    /// Download the referenced RFCs in the background
+    prefetch: bool,
+    /// Don't resume at the saved reading position
+    top: bool,
+    /// Render the HTML edition instead of paging the plain text
+    html: bool,
+    /// Read in `viewer` instead of the pager
+    tui: bool,
+    /// Skip `clean_rfc_text`, the downloaded text verbatim everywhere
+    raw: bool,
+    /// Pick the line to open at in skim first
+    find: bool,
+}
+
+/// Fetch and show an RFC. With `--refs`, keep going through whichever
+/// referenced RFC the reader picks next.
+async fn read_rfc(mut number: u32, offline: bool, view: &ReadView) {
+    let prefetched = Arc::new(AtomicUsize::new(0));
+    let mut prefetches = Vec::new();
+
+    loop {
+        let fetched = match view.html {
+            true => fetch_rfc_html(number, offline).await.map(|html| html::to_text(&html)),
+            false => fetch_rfc(number, offline).await,
+        };
+        let content = match fetched {
+            Ok(content) => content,
+            Err(e) => {
+                report(e);
+                break;
+            }
+        };
+        if view.prefetch && !offline {
+            prefetches.push(spawn_prefetch(referenced_rfcs(&content, Some(number)), prefetched.clone()));
+        }
+        let references = (view.refs || view.tui).then(|| referenced_rfcs(&content, Some(number)));
+        // The pager blocks this thread, let the prefetch run on the others meanwhile
+        let picked =
+            tokio::task::block_in_place(|| page_rfc(number, content, view, references.as_deref().unwrap_or_default()));
+        // The viewer offers the references itself
+        let next = picked.or_else(|| {
+            references.filter(|_| !view.tui).and_then(|numbers| pick_reference(&format!("RFC {}", number), &numbers))
+        });
+        match next {
+            Some(next) => number = next,
+            None => break,
+        }
+    }
+
+    if view.prefetch && !offline {
+        // Whatever is still downloading isn't worth keeping the reader waiting for
+        for task in prefetches {
+            task.abort();
+        }
+        let count = prefetched.load(Ordering::Relaxed);
+        let noun = if count == 1 { "RFC" } else { "RFCs" };
+        eprintln!("{}", format!("Prefetched {} referenced {} into the cache.", count, noun).dimmed());
+    }
+}
+
+/// How many referenced RFCs `--prefetch` downloads at once.
+const PREFETCH_CONCURRENCY: usize = 4;
+
+/// Download the uncached RFCs among `numbers` in the background, a few at a
+/// time, counting every one that lands in the cache.
+fn spawn_prefetch(numbers: Vec<u32>, prefetched: Arc<AtomicUsize>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let limit = Arc::new(tokio::sync::Semaphore::new(PREFETCH_CONCURRENCY));
+        let mut downloads = tokio::task::JoinSet::new();
+        for number in numbers.into_iter().filter(|&n| !rfc_cache_path(n).exists()) {
+            let limit = limit.clone();
+            let prefetched = prefetched.clone();
+            downloads.spawn(async move {
+                let Ok(_permit) = limit.acquire_owned().await else { return };
+                if prefetch_rfc(number).await {
+                    prefetched.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+        while downloads.join_next().await.is_some() {}
+    })
+}
+
+
+/// Every RFC mentioned as "RFC 7230", "RFC7230" or "[RFC7230]", ascending, without `own`.
+fn referenced_rfcs(text: &str, own: Option<u32>) -> Vec<u32> {
+    let ref_re = Regex::new(r"\bRFC ?(\d{1,5})\b").unwrap();
+    let mut numbers: Vec<u32> = ref_re
+        .captures_iter(text)
+        .filter_map(|caps| caps[1].parse().ok())
+        .filter(|&n| n > 0 && Some(n) != own)
+        .collect();
+    numbers.sort_unstable();
+    numbers.dedup();
+    numbers
+}
+
+/// List what `name` references and, on a terminal, ask which one to open next.
+fn pick_reference(name: &str, numbers: &[u32]) -> Option<u32> {
+    if numbers.is_empty() {
+        eprintln!("{}", format!("{} doesn't reference any RFCs.", name).dimmed());
+        return None;
+    }
+
+    // Titles come from the cached index when there is one
+    let entries = fs::read_to_string(cache_dir().join("rfc-index.txt"))
+        .map(|data| index_entries(&data))
+        .unwrap_or_default();
+    let lines: Vec<String> = numbers
+        .iter()
+        .map(|&n| match index::find(&entries, n) {
+            Some(entry) => entry.summary_line(),
+            None => format!("RFC {}", n),
+        })
+        .collect();
+
+    let noun = if numbers.len() == 1 { "RFC" } else { "RFCs" };
+    eprintln!("{}", format!("{} references {} {}:", name, numbers.len(), noun).bold());
+    if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+        for line in &lines {
+            eprintln!("  {}", line);
+        }
+        return None;
+    }
+
+    // Esc (an Err from inquire) reads as "done"
+    let choice = Select::new("Open one next?", lines.clone()).with_page_size(15).prompt().ok()?;
+    lines.iter().position(|line| *line == choice).map(|i| numbers[i])
+}
+
+/// Show an RFC the way `view` asks for: optionally cut down to a section,
+/// opened at `goto`, in the pager, in the viewer, in the editor or straight
+/// to stdout. Returns the referenced RFC picked in the viewer, if any.
+fn page_rfc(number: u32, content: String, view: &ReadView, references: &[u32]) -> Option<u32> {
+    record_read(number);
+    if !view.no_warn {
+        warn_if_obsoleted(number);
+    }
+    // The rendered HTML has its own reading position
+    let name = if view.html { format!("rfc{}-html", number) } else { format!("rfc{}", number) };
+    page_document(&name, content, view, references)
+}
+
+/// Show an RFC or draft the way `view` asks for. `name` ("rfc2616",
+/// "draft-ietf-quic-transport-34") names the editor's temporary file.
+/// `references` are offered in the viewer, the RFC picked there is returned.
+fn page_document(name: &str, content: String, view: &ReadView, references: &[u32]) -> Option<u32> {
+    let content = narrow_to_section(content, view.section.as_deref(), view.raw);
+    if view.stats {
+        print_text_stats(&content);
+    }
+    if view.stdout {
+        // Streamed, without a cleaned copy in memory. A closed pipe (`| head`)
+        // just means the reader has seen enough, and so does --head.
+        let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+        let mut left = view.head.unwrap_or(usize::MAX);
+        if view.raw {
+            // Byte for byte, line endings included
+            for line in content.split_inclusive('\n').take(left) {
+                if out.write_all(line.as_bytes()).is_err() {
+                    break;
+                }
+            }
+        } else {
+            for_each_clean_line(&content, |line| {
+                if left > 0 && writeln!(out, "{}", line).is_ok() {
+                    left -= 1;
+                } else {
+                    left = 0;
+                }
+            });
+        }
+        let _ = out.flush();
+        return None;
+    }
+    // Everything from here on shows the cleaned text, unless --raw
+    let content = if view.raw { content } else { clean_rfc_text(&content) };
+    if view.editor && view_in_editor(name, &content) {
+        return None;
+    }
+    let term = view.highlight.as_deref().and_then(highlight_term).filter(|term| {
+        content.to_ascii_lowercase().contains(&term.to_ascii_lowercase())
+    });
+    // The line found with --find opens exactly there
+    let found = match view.find {
+        true => match find_line(&content) {
+            Some(line) => Some(line),
+            None => {
+                record_failure(Failure::Cancelled);
+                return None;
+            }
+        },
+        false => None,
+    };
+    // --goto decides where to open, the highlight only does when there's neither
+    let pattern = match view.goto.as_deref() {
+        Some(goto) => goto_pattern(&content, goto),
+        None => term.filter(|_| found.is_none()).map(|term| caseless(&pager_escape(term))),
+    };
+    if view.tui {
+        let start = found
+            .or_else(|| {
+                let re = Regex::new(pattern.as_deref()?).ok()?;
+                content.lines().position(|line| re.is_match(line))
+            })
+            .unwrap_or(0);
+        match viewer::run(name, &content, references, start, term) {
+            Ok(picked) => return picked,
+            Err(e) => eprintln!("{}: the viewer failed ({}), paging instead", "Warning".yellow(), e),
+        }
+    }
+    // A section, --goto or --find says where to start, otherwise pick up where reading stopped
+    if pattern.is_none() && found.is_none() && view.section.is_none() && page_resuming(name, &content, view) {
+        return None;
+    }
+    let jump = match found {
+        Some(line) => Some(Jump::Line(line + 1)),
+        None => pattern.as_deref().map(Jump::Pattern),
+    };
+    view_in_pager(&content, view.pager.as_deref(), jump);
+    None
+}
+
+/// Page a whole document in less, starting where the reader left it last
+/// time, and remember where they leave it now. less only reports its
+/// position for files it opened itself, so the text goes through a file
+/// named after the document. False when the pager isn't less, the caller
+/// then pages as usual.
+fn page_resuming(name: &str, content: &str, view: &ReadView) -> bool {
+    let Some(mut less) = less_pager(view.pager.as_deref()) else { return false };
+    if content.contains('\x1b') {
+        less.push("-R".to_string());
+    }
+    // Per process, two terminals reading the same RFC each get their own file
+    let file = std::env::temp_dir().join(format!("rfcli-{}-{}.txt", name, std::process::id()));
+    if write_atomic(&file, content).is_err() {
+        return false;
+    }
+
+    let positions_path = cache_dir().join("positions.json");
+    let mut positions = positions::load(&positions_path);
+    match positions.get(name) {
+        Some(&offset) if offset > 0 && !view.top => {
+            eprintln!("{}", format!("Resuming {} where you stopped, --top starts over.", name).dimmed());
+            less.push(format!("+{}P", offset));
+        }
+        _ => {}
+    }
+
+    // A history file of our own, marks for the temporary files don't belong in ~/.lesshst
+    let history = cache_dir().join("less-history");
+    let status = interrupt::with_child(|| {
+        Command::new(&less[0]).args(&less[1..]).arg("--save-marks").arg(&file).env("LESSHISTFILE", &history).status()
+    });
+    let raw_history = fs::read_to_string(&history).ok();
+    let offset = raw_history.as_deref().and_then(|raw| positions::last_mark(raw, &file));
+    let _ = fs::remove_file(&file);
+    // The mark is in positions.json now, the per-process file name would never be read again
+    if let Some(raw) = raw_history {
+        let _ = write_atomic(&history, positions::prune_marks(&raw));
+    }
+    if status.is_err() {
+        return false;
+    }
+
+    if let Some(offset) = offset {
+        positions.insert(name.to_string(), offset);
+        if let Err(e) = positions::save(&positions_path, &positions) {
+            eprintln!("{}: could not save the reading position: {}", "Warning".yellow(), e);
+        }
+    }
+    true
+}
+
+/// The less command line `view_in_pager` would end up running, None when
+/// it would pick another pager (a custom one, or bat when installed).
+fn less_pager(pager: Option<&str>) -> Option<Vec<String>> {
+    let env_pager = std::env::var("PAGER").ok();
+    if let Some(custom) = [pager, env_pager.as_deref()].into_iter().flatten().find(|p| !p.trim().is_empty()) {
+        let parts: Vec<String> = custom.split_whitespace().map(String::from).collect();
+        let is_less = std::path::Path::new(&parts[0]).file_name().is_some_and(|name| name == "less");
+        return is_less.then_some(parts);
+    }
+    if Command::new("bat").arg("--version").stdout(Stdio::null()).status().is_ok() {
+        return None;
+    }
+    Some(vec!["less".to_string(), "-FK".to_string()])
+}
+
+/// Open `text` in `$VISUAL` or `$EDITOR` through a temporary file that is removed
+/// afterwards. False when no editor is configured or it can't be started.
+fn view_in_editor(name: &str, text: &str) -> bool {
+    let Some(editor) = ["VISUAL", "EDITOR"].iter().find_map(|var| std::env::var(var).ok().filter(|v| !v.trim().is_empty())) else {
+        eprintln!("{}: neither $VISUAL nor $EDITOR is set, using the pager", "Warning".yellow());
+        return false;
+    };
+
+    let path = std::env::temp_dir().join(format!("rfcli-{}-{}.txt", name, std::process::id()));
+    if let Err(e) = fs::write(&path, text) {
+        print_error(format!("{}: {}", path.display(), e));
+        return false;
+    }
+
+    let parts: Vec<&str> = editor.split_whitespace().collect();
+    let status = interrupt::with_child(|| Command::new(parts[0]).args(&parts[1..]).arg(&path).status());
+    let _ = fs::remove_file(&path);
+    match status {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!("{}: could not start {}: {}, using the pager", "Warning".yellow(), parts[0], e);
+            false
+        }
+    }
+}
+
+/// Reading speed behind the `--stats` estimate, in words per minute.
+const READING_WPM: usize = 200;
+
+/// "4,213 words, 612 lines, about 22 min to read" for the cleaned text.
+fn print_text_stats(content: &str) {
+    let (mut words, mut lines) = (0, 0);
+    for_each_clean_line(content, |line| {
+        if !line.is_empty() {
+            words += line.split_whitespace().count();
+            lines += 1;
+        }
+    });
+    let minutes = words.div_ceil(READING_WPM).max(1);
+    eprintln!(
+        "{} words, {} lines, about {} min to read",
+        group_thousands(words).bold(),
+        group_thousands(lines).bold(),
+        minutes.to_string().bold()
+    );
+}
+
+/// 12345 -> "12,345"
+fn group_thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Search pattern for `--goto`: the heading of a matching section if there is one,
+/// otherwise the text itself. Text that isn't in the document leaves the pager at the top.
+fn goto_pattern(content: &str, goto: &str) -> Option<String> {
+    let sections = sections::parse_sections(content);
+    if let Some(section) = sections::find_section(&sections, goto) {
+        let heading = content.lines().nth(section.start).unwrap_or_default();
+        return Some(format!("^{}", pager_escape(heading.trim_end())));
+    }
+    if content.contains(goto) {
+        return Some(pager_escape(goto));
+    }
+    eprintln!("{}: '{}' not found, opening at the top", "Warning".yellow(), goto);
+    None
+}
+
+/// The word of a picker query to highlight: skim's operators (`'exact`,
+/// `^prefix`, `suffix$`) stripped and `!negated` terms skipped. less finds
+/// one pattern at a time, so of several words the longest is used.
+fn highlight_term(query: &str) -> Option<&str> {
+    query
+        .split_whitespace()
+        .filter(|term| !term.starts_with('!') && *term != "|")
+        .map(|term| term.trim_start_matches(['\'', '^']).trim_end_matches('$'))
+        .filter(|term| !term.is_empty())
+        .rev()
+        .max_by_key(|term| term.chars().count())
+}
+
+/// A pager pattern matching in any case: "http" becomes "[hH][tT][tT][pP]".
+/// Spelled out because less (and bat's less) only searches ignoring case with -i.
+fn caseless(pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len() * 4);
+    for c in pattern.chars() {
+        if c.is_ascii_alphabetic() {
+            out.extend(['[', c.to_ascii_lowercase(), c.to_ascii_uppercase(), ']']);
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Escape regex metacharacters for less' search, which only knows basic POSIX syntax.
+fn pager_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if r"\.^$*+?()[]{}|".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Cut `content` down to the section matching `query`, cleaned unless `raw`.
+/// Without a match the whole document is kept, after a warning.
+fn narrow_to_section(content: String, query: Option<&str>, raw: bool) -> String {
+    let Some(query) = query else { return content };
+    let cleaned = if raw { Cow::Borrowed(content.as_str()) } else { Cow::Owned(clean_rfc_text(&content)) };
+    let sections = sections::parse_sections(&cleaned);
+    match sections::find_section(&sections, query) {
+        Some(section) => sections::section_text(&cleaned, section),
+        None => {
+            eprintln!("{}: no section matching '{}', showing the whole document", "Warning".yellow(), query);
+            content
+        }
+    }
+}
+
+/// The RFCs behind a subseries number such as STD 66 or BCP 14.
+async fn resolve_subseries(series: index::Subseries, number: u32, refresh: bool, offline: bool) -> Option<Vec<u32>> {
+    let label = format!("{} index", series.name());
+    let data = load_index_file(series.index_file(), &label, refresh, offline).await?;
+    let numbers = index::subseries_rfcs(&data, number);
+    if numbers.is_empty() {
+        print_error(format!("{} {} does not contain any RFCs", series.name(), number));
+        return None;
+    }
+    Some(numbers)
+}
+
+/// With the `sqlite` feature, the cached RFCs whose text contains `term`
+/// according to FTS5, indexing any cached texts it hasn't seen yet first.
+/// None means "scan everything", which is also the fallback on errors.
+#[cfg(feature = "sqlite")]
+fn fts_candidates(term: &str, numbers: &[u32]) -> Option<Vec<u32>> {
+    let mut store = open_store()?;
+    let result = (|| {
+        let indexed = store.indexed_bodies()?;
+        let missing: Vec<u32> = numbers.iter().copied().filter(|n| !indexed.contains(n)).collect();
+        store.index_bodies(&missing, |number| read_cached_rfc(number).ok().map(|raw| clean_rfc_text(&raw)))?;
+        store.search(term)
+    })();
+    match result {
+        // FTS5 matches whole words, so a partial word finds nothing there but may in the scan
+        Ok(hits) if !hits.is_empty() => Some(hits.into_iter().filter(|n| numbers.contains(n)).collect()),
+        Ok(_) => None,
+        Err(e) => {
+            eprintln!("{}: full-text search failed, scanning the cache instead: {}", "Warning".yellow(), e);
+            None
+        }
+    }
+}
+
+/// `rfc info`: from the RFC's own metadata, or its index entry without it.
+async fn print_info(number: u32, offline: bool) {
+    let entry = match fetch_metadata(number, offline).await {
+        Ok(metadata) if !metadata.title.trim().is_empty() => metadata.to_entry(number),
+        _ => {
+            let Some(index_data) = load_index(false, offline).await else { return };
+            let Some(entry) = index::find(&index_entries(&index_data), number).cloned() else {
+                record_failure(Failure::NotFound);
+                print_error(format!("RFC {} is not in the index", number));
+                return;
+            };
+            entry
+        }
+    };
+
+    if json_output() {
+        print_json(&entry);
+        return;
+    }
+
+    let refs = |numbers: &[u32]| numbers.iter().map(|n| format!("RFC{}", n)).collect::<Vec<_>>().join(", ");
+
+    println!("{} {}", "RFC".bold(), number.to_string().bold().yellow());
+    println!("  {:<14} {}", "Title:".cyan(), entry.title.bold());
+    if !entry.authors.is_empty() {
+        println!("  {:<14} {}", "Authors:".cyan(), entry.authors.join(", "));
+    }
+    if !entry.date.is_empty() {
+        println!("  {:<14} {}", "Published:".cyan(), entry.date);
+    }
+    if !entry.status.is_empty() {
+        println!("  {:<14} {}", "Status:".cyan(), color_status(&entry.status));
+    }
+    for (label, numbers) in [
+        ("Obsoletes:", &entry.obsoletes),
+        ("Obsoleted by:", &entry.obsoleted_by),
+        ("Updates:", &entry.updates),
+        ("Updated by:", &entry.updated_by),
+    ] {
+        if !numbers.is_empty() {
+            println!("  {:<14} {}", label.cyan(), refs(numbers));
+        }
+    }
+    if let Some(mirror) = download_sources().get(&format!("rfc{}.txt", number)) {
+        println!("  {:<14} {}", "Fetched from:".cyan(), mirror);
+    }
+}
+
+fn export_rfc(number: u32, content: &str, format: ExportFormat, output: Option<&std::path::Path>) {
+    let cleaned = clean_rfc_text(content);
+    let text = match format {
+        ExportFormat::Md => {
+            let title = cached_index_entry(number).map(|entry| entry.title);
+            markdown::to_markdown(&cleaned, number, title.as_deref())
+        }
+        ExportFormat::Txt => cleaned,
+    };
+
+    match output {
+        Some(path) => match fs::write(path, text) {
+            Ok(()) => eprintln!("{} {}", "Wrote".green(), path.display()),
+            Err(e) => print_error(format!("{}: {}", path.display(), e)),
+        },
+        None => print!("{}", text),
+    }
+}
+
+fn export_abnf(number: u32, content: &str, output: Option<&std::path::Path>) {
+    let rules = abnf::extract(&clean_rfc_text(content));
+    if rules.is_empty() {
+        eprintln!("{}", format!("No ABNF rules found in RFC {}.", number).yellow());
+        return;
+    }
+
+    match output {
+        Some(path) => match fs::write(path, rules) {
+            Ok(()) => eprintln!("{} {}", "Wrote".green(), path.display()),
+            Err(e) => print_error(format!("{}: {}", path.display(), e)),
+        },
+        None => print!("{}", rules),
+    }
+}
+
+/// Page a colored diff of the cleaned texts of RFC `a` and RFC `b`.
+async fn diff_rfcs(a: u32, b: u32, side_by_side: bool, context: usize, offline: bool) {
+    let mut texts = Vec::new();
+    for number in [a, b] {
+        match fetch_rfc(number, offline).await {
+            Ok(content) => texts.push(clean_rfc_text(&content)),
+            Err(e) => {
+                report(e);
+                return;
+            }
+        }
+    }
+
+    let (old_name, new_name) = (format!("RFC {}", a), format!("RFC {}", b));
+    let out = if side_by_side {
+        let cols = termsize::get().map(|t| t.cols as usize).unwrap_or(160);
+        diff::side_by_side(&texts[0], &texts[1], &old_name, &new_name, (cols.saturating_sub(3) / 2).max(20), context)
+    } else {
+        diff::unified(&texts[0], &texts[1], &old_name, &new_name, context)
+    };
+    if out.is_empty() {
+        println!("{}", format!("{} and {} have the same text.", old_name, new_name).green());
+        return;
+    }
+    view_in_pager(&out, config::get().pager.as_deref(), None);
+}
+
+/// Report what replaces `number`, returning the newest replacement.
+async fn print_latest(number: u32, offline: bool) -> Option<u32> {
+    let index_data = load_index(false, offline).await?;
+    let entries = index_entries(&index_data);
+    if index::find(&entries, number).is_none() {
+        record_failure(Failure::NotFound);
+        print_error(format!("RFC {} is not in the index", number));
+        return None;
+    }
+
+    let current = index::current_replacements(&entries, number);
+    if current.is_empty() {
+        println!("{} RFC {} is current, nothing obsoletes it.", "✓".green().bold(), number);
+        return None;
+    }
+
+    println!("RFC {} is obsoleted. Current replacement{}:", number, if current.len() == 1 { "" } else { "s" });
+    for n in &current {
+        match index::find(&entries, *n) {
+            Some(entry) => println!("  {}", entry.summary_line().bold()),
+            None => println!("  RFC {}", n),
+        }
+    }
+    current.last().copied()
+}
+
+/// A picker row. Skim matches on the summary line but hands back just the number.
+struct RfcItem {
+    entry: index::RfcEntry,
+    line: String,
+}
+
+impl SkimItem for RfcItem {
+    fn text(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.line)
+    }
+
+    fn output(&self) -> Cow<'_, str> {
+        Cow::Owned(self.entry.number.to_string())
+    }
+
+    fn preview(&self, _context: PreviewContext) -> ItemPreview {
+        ItemPreview::AnsiText(preview_text(&self.entry))
+    }
+}
+
+/// Preview pane contents: metadata from the index plus the abstract if the RFC is cached.
+/// Never touches the network, the picker must stay responsive.
+fn preview_text(entry: &index::RfcEntry) -> String {
+    let mut out = format!("RFC {}\n{}\n", entry.number, entry.title);
+    if !entry.authors.is_empty() {
+        out.push_str(&format!("\n{}", entry.authors.join(", ")));
+    }
+    if !entry.date.is_empty() {
+        out.push_str(&format!("\n{}", entry.date));
+    }
+    if !entry.status.is_empty() {
+        out.push_str(&format!("\nStatus: {}", color_status(&entry.status)));
+    }
+    if !entry.obsoleted_by.is_empty() {
+        let refs: Vec<String> = entry.obsoleted_by.iter().map(|n| format!("RFC {}", n)).collect();
+        out.push_str(&format!("\nObsoleted by: {}", refs.join(", ")));
+    }
+    out.push_str("\n\n");
+
+    match read_cached_rfc(entry.number) {
+        Ok(text) => match extract_abstract(&text) {
+            Some(abstract_text) => out.push_str(&abstract_text),
+            None => out.push_str("(no abstract found)"),
+        },
+        Err(_) => out.push_str("(not downloaded yet)"),
+    }
+    out
+}
+
+/// `rfc abstract`: from the RFC's metadata, or read out of its text.
+async fn print_abstract(number: u32, offline: bool) {
+    let metadata = fetch_metadata(number, offline).await.ok();
+    let abstract_text = match metadata.as_ref().and_then(|metadata| metadata.abstract_text()) {
+        Some(text) => Some(text),
+        None => match fetch_rfc(number, offline).await {
+            Ok(content) => extract_abstract(&clean_rfc_text(&content)),
+            Err(e) => {
+                report(e);
+                return;
+            }
+        },
+    };
+
+    let title = metadata
+        .map(|metadata| metadata.title.trim().to_string())
+        .filter(|title| !title.is_empty())
+        .or_else(|| cached_index_entry(number).map(|entry| entry.title));
+    match title {
+        Some(title) => println!("{} {}  {}", "RFC".bold(), number.to_string().bold().yellow(), title.bold()),
+        None => println!("{} {}", "RFC".bold(), number.to_string().bold().yellow()),
+    }
+    println!();
+
+    let Some(abstract_text) = abstract_text else {
+        println!("  {}", "(no abstract in this RFC)".dimmed());
+        return;
+    };
+    // Same width rules as summaries, the text is re-wrapped paragraph by paragraph
+    let options = Options::new(tldr_width(None) - 4).initial_indent("  ").subsequent_indent("  ");
+    let paragraphs: Vec<String> = abstract_text
+        .split("\n\n")
+        .map(|paragraph| wrap(&unwrap_lines(paragraph), &options).join("\n"))
+        .collect();
+    println!("{}", paragraphs.join("\n\n"));
+}
+
+/// `rfc authors`: the RFC's metadata says who, the "Authors' Addresses"
+/// section adds their affiliation and email. Without metadata the section
+/// alone, or the index when the text has none.
+async fn print_authors(number: u32, offline: bool) {
+    let listed = fetch_metadata(number, offline).await.map(|metadata| metadata.authors).unwrap_or_default();
+    let content = match fetch_rfc(number, offline).await {
+        Ok(content) => content,
+        // The names alone will do
+        Err(_) if !listed.is_empty() => String::new(),
+        Err(e) => {
+            report(e);
+            return;
+        }
+    };
+
+    let mut authors = authors::parse_authors(&clean_rfc_text(&content));
+    if !listed.is_empty() {
+        authors = listed.iter().map(|name| addressed_author(name, &authors)).collect();
+    }
+    if authors.is_empty() {
+        let entry = match load_index(false, offline).await {
+            Some(data) => index::find(&index_entries(&data), number).cloned(),
+            None => None,
+        };
+        match entry.filter(|entry| !entry.authors.is_empty()) {
+            Some(entry) => {
+                for name in &entry.authors {
+                    println!("  {} {}", "•".cyan().bold(), name.bold());
+                }
+            }
+            None => eprintln!("{}", format!("No authors found for RFC {}.", number).yellow()),
+        }
+        return;
+    }
+
+    for author in authors {
+        let mut line = format!("  {} {}", "•".cyan().bold(), author.name.bold());
+        if let Some(affiliation) = &author.affiliation {
+            line.push_str(&format!(", {}", affiliation));
+        }
+        if let Some(email) = &author.email {
+            line.push_str(&format!(" {}", format!("<{}>", email).dimmed()));
+        }
+        println!("{}", line);
+    }
+}
+
+/// The author `name` ("R. Fielding, Ed.") of an RFC's metadata, with the
+/// details of the one in `addressed` sharing their surname if there is one.
+fn addressed_author(name: &str, addressed: &[authors::Author]) -> authors::Author {
+    let surname = name.split(',').next().and_then(|name| name.split_whitespace().last()).unwrap_or(name);
+    let surname = surname.to_lowercase();
+    addressed
+        .iter()
+        .find(|author| author.name.to_lowercase().split(|c: char| !c.is_alphanumeric() && c != '-').any(|word| word == surname))
+        .cloned()
+        .unwrap_or_else(|| authors::Author { name: name.trim().to_string(), affiliation: None, email: None })
+}
+
+/// Join a paragraph's lines back into one. A line ending in a hyphen was
+/// broken inside a word like "application-level", so no space goes there.
+fn unwrap_lines(paragraph: &str) -> String {
+    let mut out = String::new();
+    for line in paragraph.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if !out.is_empty() && !out.ends_with('-') {
+            out.push(' ');
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+/// The body of the "Abstract" section, dedented, or None for RFCs without one.
+fn extract_abstract(text: &str) -> Option<String> {
+    let mut lines = text.lines().skip_while(|line| !line.trim().eq_ignore_ascii_case("abstract"));
+    lines.next()?;
+
+    // The section ends at the next heading, which starts in column 0
+    let body: Vec<&str> = lines
+        .take_while(|line| line.trim().is_empty() || line.starts_with(char::is_whitespace))
+        .map(|line| line.trim())
+        .collect();
+    let abstract_text = body.join("\n").trim().to_string();
+    (!abstract_text.is_empty()).then_some(abstract_text)
+}
+
+async fn fuzzy_select_rfc(
+    force_refresh: bool,
+    query: Option<String>,
+    offline: bool,
+    filter: &EntryFilter,
+    preview_window: Option<&str>,
+    only: Option<&[u32]>,
+) -> Option<u32> {
+    fuzzy_select_rfcs(force_refresh, query, offline, filter, preview_window, only, false)
+        .await?
+        .into_iter()
+        .next()
+}
+
+/// The picker itself. With `multi`, Tab marks several RFCs and all of them are returned.
+async fn fuzzy_select_rfcs(
+    force_refresh: bool,
+    query: Option<String>,
+    offline: bool,
+    filter: &EntryFilter,
+    preview_window: Option<&str>,
+    only: Option<&[u32]>,
+    multi: bool,
+) -> Option<Vec<u32>> {
+    let index_data = load_index(force_refresh, offline).await?;
+
+    // Rule out what we can before skim takes over the screen
+    let cached = offline.then(cached_rfc_numbers);
+    if cached.as_ref().is_some_and(|numbers| numbers.is_empty()) {
+        record_failure(Failure::NotFound);
+        eprintln!("{}", "No cached RFCs to choose from (offline mode).".yellow());
+        return None;
+    }
+    if only.is_some_and(|numbers| numbers.is_empty()) {
+        record_failure(Failure::NotFound);
+        eprintln!("{}", "No bookmarked RFCs to choose from, add some with `rfcli bookmark add`.".yellow());
+        return None;
+    }
+
+    // Parse on another thread and stream the entries in, skim shows up right
+    // away and fills in as they arrive instead of waiting for the whole index
+    let (tx, items): (SkimItemSender, SkimItemReceiver) = unbounded();
+    let wanted_entry = filter.clone();
+    let only_numbers = only.map(<[u32]>::to_vec);
+    let producer = std::thread::spawn(move || {
+        let mut sent = 0;
+        each_index_entry(&index_data, |entry| {
+            // Offline, only RFCs we can actually open are worth showing
+            let wanted = cached.as_ref().is_none_or(|numbers| numbers.binary_search(&entry.number).is_ok())
+                && wanted_entry.matches(&entry)
+                && only_numbers.as_ref().is_none_or(|numbers| numbers.contains(&entry.number));
+            if !wanted {
+                return true;
+            }
+            let line = entry.summary_line();
+            sent += 1;
+            // Fails once skim is gone, no need to parse the rest then
+            tx.send(Arc::new(RfcItem { entry, line })).is_ok()
+        });
+        sent
+    });
+
+    let picked = run_picker(items, query, preview_window, multi);
+    if producer.join().unwrap_or(0) == 0 {
+        record_failure(Failure::NotFound);
+        if offline {
+            eprintln!("{}", "None of the cached RFCs match (offline mode).".yellow());
+        } else if !filter.status.is_empty() {
+            eprintln!("{} {}", "No RFCs match status".yellow(), filter.status.join(", ").yellow().bold());
+        } else if let Some(name) = &filter.author {
+            eprintln!("{} {}", "No RFCs by an author matching".yellow(), name.yellow().bold());
+        } else if filter.dates.is_set() {
+            eprintln!("{} {}", "No RFCs published in".yellow(), filter.dates.describe().yellow().bold());
+        } else if only.is_some() {
+            eprintln!("{}", "None of those RFCs are in the index, try --refresh.".yellow());
+        } else {
+            eprintln!("{}", "The RFC index is empty, try --refresh.".yellow());
+        }
+        return None;
+    }
+    picked
+}
+
+/// A finished list of entries for `run_picker`.
+fn picker_items(entries: Vec<index::RfcEntry>) -> SkimItemReceiver {
+    let (tx, items): (SkimItemSender, SkimItemReceiver) = unbounded();
+    for entry in entries {
+        let line = entry.summary_line();
+        let _ = tx.send(Arc::new(RfcItem { entry, line }));
+    }
+    items // Dropping the sender closes the channel, so skim knows the list is complete
+}
+
+/// Show `items` in skim and return the picked numbers, None on Esc.
+/// Items may still be coming in while skim runs.
+fn run_picker(items: SkimItemReceiver, query: Option<String>, preview_window: Option<&str>, multi: bool) -> Option<Vec<u32>> {
+    let mut options_builder = SkimOptionsBuilder::default();
+    options_builder
+        .height(Some("50%"))
+        .multi(multi)
+        // An empty command enables the pane, the content comes from RfcItem::preview
+        .preview(Some(""))
+        .preview_window(Some(preview_window.unwrap_or("right:50%:wrap")))
+        .bind(vec!["esc:abort", "ctrl-c:abort"]);
+
+    // If a query was provided, set it as the initial search text
     if let Some(ref q) = query {
         options_builder.query(Some(q));
     }
 
     let options = options_builder.build().unwrap();
-    let output = Skim::run_with(&options, Some(items));
+    // Skim drives the terminal synchronously, so hand this worker thread over to it
+    let output = tokio::task::block_in_place(|| Skim::run_with(&options, Some(items)));
 
     // Check if the user aborted (pressed ESC)
     if let Some(out) = output {
@@ -278,34 +2343,611 @@ fn fuzzy_select_rfc(force_refresh: bool, query: Option<String>) -> Option<u32> {
             return None; // This will trigger the 'break' in your loop
         }
         
-        out.selected_items.first().and_then(|item| {
-            item.output().split_whitespace().next()?.parse::<u32>().ok()
-        })
-
-        
+        let numbers: Vec<u32> = out.selected_items.iter()
+            .filter_map(|item| parse_rfc_id(item.output().split_whitespace().next()?))
+            .collect();
+        (!numbers.is_empty()).then_some(numbers)
     } else {
         None
     }
 }
 
-// Ensure there is only ONE argument here: content
-fn view_in_pager(content: &str) {
-    let (cmd, args) = if Command::new("bat").arg("--version").stdout(Stdio::null()).status().is_ok() {
-        // We use the 'man' language and 'plain' flags for those nice colors
-        ("bat", vec!["-l", "man", "-p", "--pager", "less -FK"])
+/// Print the section headings, or with `pick` choose one and page it.
+fn show_toc(content: &str, pick: bool) {
+    let cleaned = clean_rfc_text(content);
+    let sections = sections::parse_sections(&cleaned);
+    if sections.is_empty() {
+        eprintln!("{}", "No numbered sections found in this RFC.".yellow());
+        return;
+    }
+
+    if !pick {
+        for section in &sections {
+            let indent = "  ".repeat(section.depth - 1);
+            println!("{}{:<8} {}", indent, section.number.cyan(), section.title);
+        }
+        return;
+    }
+
+    let (tx, items): (SkimItemSender, SkimItemReceiver) = unbounded();
+    for section in &sections {
+        let line = format!("{}{}  {}", "  ".repeat(section.depth - 1), section.number, section.title);
+        let _ = tx.send(Arc::new(line));
+    }
+    drop(tx);
+
+    let options = SkimOptionsBuilder::default()
+        .height(Some("50%"))
+        .multi(false)
+        .bind(vec!["esc:abort", "ctrl-c:abort"])
+        .build()
+        .unwrap();
+    let Some(out) = tokio::task::block_in_place(|| Skim::run_with(&options, Some(items))) else { return };
+    if out.final_event == Event::EvActAbort {
+        return;
+    }
+    let Some(number) = out.selected_items.first().and_then(|item| item.output().split_whitespace().next().map(String::from)) else {
+        return;
+    };
+    if let Some(section) = sections.iter().find(|s| s.number == number) {
+        let heading = cleaned.lines().nth(section.start).unwrap_or_default();
+        let pattern = format!("^{}", pager_escape(heading.trim_end()));
+        view_in_pager(&cleaned, config::get().pager.as_deref(), Some(Jump::Pattern(&pattern)));
+    }
+}
+
+/// `read --find`: every non-blank line of `text` in skim, numbered. The
+/// index of the line picked, None on Esc.
+fn find_line(text: &str) -> Option<usize> {
+    let lines: Vec<&str> = text.lines().collect();
+    let (tx, items): (SkimItemSender, SkimItemReceiver) = unbounded();
+    for (i, line) in lines.iter().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let _ = tx.send(Arc::new(format!("{:>6}  {}", i + 1, line.trim())));
+    }
+    drop(tx);
+
+    let options = SkimOptionsBuilder::default()
+        .height(Some("50%"))
+        .multi(false)
+        .bind(vec!["esc:abort", "ctrl-c:abort"])
+        .build()
+        .unwrap();
+    let out = tokio::task::block_in_place(|| Skim::run_with(&options, Some(items)))?;
+    if out.final_event == Event::EvActAbort {
+        return None;
+    }
+    let number: usize = out.selected_items.first()?.output().split_whitespace().next()?.parse().ok()?;
+    (1..=lines.len()).contains(&number).then(|| number - 1)
+}
+
+/// Print the URL (handy over SSH) and hand it to the platform's opener.
+fn open_in_browser(url: &str) {
+    println!("{}", url.cyan().underline());
+
+    let opener = if cfg!(target_os = "macos") {
+        Command::new("open").arg(url).stdout(Stdio::null()).stderr(Stdio::null()).status()
+    } else if cfg!(target_os = "windows") {
+        Command::new("cmd").args(["/C", "start", "", url]).status()
+    } else {
+        Command::new("xdg-open").arg(url).stdout(Stdio::null()).stderr(Stdio::null()).status()
+    };
+
+    if !matches!(opener, Ok(status) if status.success()) {
+        eprintln!("{}", "Could not launch a browser, open the URL above manually.".yellow());
+    }
+}
+
+/// Sorting is stable, ties stay in index order either way.
+async fn list_entries(
+    query: Option<&str>,
+    filter: &EntryFilter,
+    sort: SortKey,
+    reverse: bool,
+    limit: Option<usize>,
+    offline: bool,
+) {
+    let Some(index_data) = load_index(false, offline).await else { return };
+    let query = query.map(|q| q.to_lowercase());
+
+    let mut entries: Vec<index::RfcEntry> = index_entries(&index_data).into_iter()
+        .filter(|entry| filter.matches(entry))
+        .filter(|entry| match &query {
+            Some(q) => entry.number.to_string() == *q || entry.title.to_lowercase().contains(q.as_str()),
+            None => true,
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        // Undated entries stay at the end when the dates are reversed
+        if sort == SortKey::Date && a.published().is_none() != b.published().is_none() {
+            return a.published().is_none().cmp(&b.published().is_none());
+        }
+        let ordering = sort.compare(a, b);
+        if reverse { ordering.reverse() } else { ordering }
+    });
+    entries.truncate(limit.unwrap_or(usize::MAX));
+
+    if json_output() {
+        print_json(&entries);
     } else {
-        ("less", vec!["-FK"])
+        for entry in &entries {
+            if entry.status.is_empty() {
+                println!("{}", entry.summary_line());
+            } else {
+                println!("RFC {}  {}  [{}]", entry.number, entry.title, color_status(&entry.status));
+            }
+        }
+    }
+}
+
+/// The `--status`, date and `--author` options `read`'s picker and `list` narrow the index with.
+#[derive(Clone, Default)]
+struct EntryFilter {
+    status: Vec<String>,
+    dates: index::YearRange,
+    author: Option<String>,
+}
+
+impl EntryFilter {
+    fn matches(&self, entry: &index::RfcEntry) -> bool {
+        (self.status.is_empty() || self.status.iter().any(|s| index::status_matches(&entry.status, s)))
+            && self.dates.matches(entry)
+            && self.author.as_deref().is_none_or(|name| index::author_matches(&entry.authors, name))
+    }
+}
+
+/// The date filter of `read`/`list`, `--year` being shorthand for both ends.
+fn year_range(since: Option<u32>, until: Option<u32>, year: Option<u32>, include_undated: bool) -> index::YearRange {
+    index::YearRange { since: year.or(since), until: year.or(until), include_undated }
+}
+
+/// An index status in the color of its standing, from green for full
+/// standards down to dim red for historic documents.
+fn color_status(status: &str) -> colored::ColoredString {
+    match status.to_ascii_uppercase().as_str() {
+        "INTERNET STANDARD" => status.green().bold(),
+        "PROPOSED STANDARD" => status.cyan(),
+        "DRAFT STANDARD" => status.blue(),
+        "BEST CURRENT PRACTICE" => status.magenta(),
+        "EXPERIMENTAL" => status.yellow(),
+        "HISTORIC" | "OBSOLETE" => status.red().dimmed(),
+        "UNKNOWN" => status.dimmed(),
+        _ => status.normal(),
+    }
+}
+
+/// Pick a random published RFC from the index.
+async fn random_rfc(status: &[String], offline: bool) -> Option<u32> {
+    let index_data = load_index(false, offline).await?;
+    let candidates: Vec<index::RfcEntry> = index_entries(&index_data).into_iter()
+        .filter(|entry| entry.title != "Not Issued")
+        .filter(|entry| !offline || rfc_cache_path(entry.number).exists())
+        .filter(|entry| status.is_empty() || status.iter().any(|s| index::status_matches(&entry.status, s)))
+        .collect();
+    if candidates.is_empty() {
+        record_failure(Failure::NotFound);
+        print_error("no RFCs to pick from");
+        return None;
+    }
+
+    // RandomState is seeded from the OS, which is plenty for picking a document
+    let roll = std::collections::hash_map::RandomState::new().build_hasher().finish();
+    let entry = &candidates[(roll % candidates.len() as u64) as usize];
+    eprintln!("{}", entry.summary_line().dimmed());
+    Some(entry.number)
+}
+
+async fn search_cached(term: &str, download: bool, offline: bool) {
+    let term_re = match Regex::new(&format!("(?i){}", regex::escape(term))) {
+        Ok(re) => re,
+        Err(e) => {
+            print_error(e);
+            return;
+        }
+    };
+
+    // Titles come from whatever index is cached, search itself never needs it
+    let entries = fs::read_to_string(cache_dir().join("rfc-index.txt"))
+        .map(|data| index_entries(&data))
+        .unwrap_or_default();
+
+    let mut numbers = cached_rfc_numbers();
+    if download && !offline {
+        let missing: Vec<u32> = entries.iter()
+            .filter(|entry| term_re.is_match(&entry.title) && !numbers.contains(&entry.number))
+            .map(|entry| entry.number)
+            .collect();
+        for number in missing {
+            println!("{}", format!("Fetching RFC {}...", number).dimmed());
+            if fetch_rfc(number, offline).await.is_ok() {
+                numbers.push(number);
+            }
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    if let Some(candidates) = fts_candidates(term, &numbers) {
+        numbers = candidates;
+    }
+
+    // (number, match count, first few matching lines)
+    let mut hits: Vec<(u32, usize, Vec<String>)> = Vec::new();
+    for number in numbers {
+        let Ok(raw) = read_cached_rfc(number) else { continue };
+        let text = clean_rfc_text(&raw);
+        let mut count = 0;
+        let mut samples = Vec::new();
+        for line in text.lines() {
+            let matches = term_re.find_iter(line).count();
+            if matches > 0 {
+                count += matches;
+                if samples.len() < 3 {
+                    samples.push(highlight(line.trim(), &term_re));
+                }
+            }
+        }
+        if count > 0 {
+            hits.push((number, count, samples));
+        }
+    }
+
+    if hits.is_empty() {
+        println!("{} '{}'", "No cached RFCs mention".yellow(), term);
+        return;
+    }
+
+    // Most matches first, ties by RFC number
+    hits.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    for (number, count, samples) in hits {
+        let title = index::find(&entries, number).map(|e| e.title.as_str()).unwrap_or("");
+        let noun = if count == 1 { "match" } else { "matches" };
+        println!("{} {}  {}", "RFC".bold(), number.to_string().bold().yellow(), title.bold());
+        println!("  {}", format!("{} {}", count, noun).dimmed());
+        for sample in samples {
+            println!("    {}", sample);
+        }
+    }
+}
+
+/// Color every match of `re` in `line`.
+fn highlight(line: &str, re: &Regex) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut last = 0;
+    for m in re.find_iter(line) {
+        out.push_str(&line[last..m.start()]);
+        out.push_str(&m.as_str().red().bold().to_string());
+        last = m.end();
+    }
+    out.push_str(&line[last..]);
+    out
+}
+
+fn history_path() -> PathBuf {
+    cache_dir().join("history.jsonl")
+}
+
+/// The index entry for `number` from the cached index, never downloading it.
+fn cached_index_entry(number: u32) -> Option<index::RfcEntry> {
+    let data = fs::read_to_string(cache_dir().join("rfc-index.txt")).ok()?;
+    index::find(&index_entries(&data), number).cloned()
+}
+
+/// Remember that an RFC was opened, with its title if the index is cached.
+fn record_read(number: u32) {
+    let title = cached_index_entry(number).map(|entry| entry.title).unwrap_or_default();
+    if let Err(e) = history::record(&history_path(), &history::HistoryEntry::now(number, title)) {
+        eprintln!("{}: could not update the reading history: {}", "Warning".yellow(), e);
+    }
+}
+
+/// Banner for superseded documents, so nobody implements a dead spec by accident.
+fn warn_if_obsoleted(number: u32) {
+    let Some(entry) = cached_index_entry(number) else { return };
+    if entry.obsoleted_by.is_empty() {
+        return;
+    }
+    let refs: Vec<String> = entry.obsoleted_by.iter().map(|n| n.to_string()).collect();
+    eprintln!(
+        "{}",
+        format!("⚠ RFC {} is obsoleted by RFC {}", number, refs.join(", ")).yellow().bold()
+    );
+}
+
+fn show_history(limit: usize, clear: bool) {
+    let path = history_path();
+    if clear {
+        match fs::remove_file(&path) {
+            Ok(()) => println!("{}", "Reading history cleared.".green()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => println!("{}", "Reading history is already empty.".green()),
+            Err(e) => print_error(format!("{}: {}", path.display(), e)),
+        }
+        return;
+    }
+
+    let entries = history::recent(&path);
+    if json_output() {
+        print_json(&entries.iter().take(limit).collect::<Vec<_>>());
+        return;
+    }
+    if entries.is_empty() {
+        println!("{}", "No RFCs read yet.".yellow());
+        return;
+    }
+
+    let now = history::unix_now();
+    for entry in entries.iter().take(limit) {
+        let age = format_age(std::time::Duration::from_secs(now.saturating_sub(entry.timestamp)));
+        println!("{} {:<6} {}  {}", "RFC".bold(), entry.number.to_string().bold().yellow(), entry.title, age.dimmed());
+    }
+}
+
+/// `bookmarks.json` next to the config file.
+fn bookmarks_path() -> PathBuf {
+    config::config_dir().unwrap_or_else(cache_dir).join("bookmarks.json")
+}
+
+async fn manage_bookmarks(action: BookmarkAction, offline: bool) {
+    let path = bookmarks_path();
+    let mut saved = bookmarks::load(&path);
+
+    let done = match action {
+        BookmarkAction::Add { number } => {
+            if saved.iter().any(|b| b.number == number) {
+                println!("RFC {} is already bookmarked.", number);
+                return;
+            }
+            // Without an index the bookmark still works, it just has no title
+            let title = match load_index(false, offline).await {
+                Some(data) => match index::find(&index_entries(&data), number) {
+                    Some(entry) => entry.title.clone(),
+                    None => {
+                        record_failure(Failure::NotFound);
+                        print_error(format!("RFC {} is not in the index", number));
+                        return;
+                    }
+                },
+                None => String::new(),
+            };
+            saved.push(bookmarks::Bookmark { number, title });
+            saved.sort_by_key(|b| b.number);
+            format!("Bookmarked RFC {}.", number)
+        }
+        BookmarkAction::Remove { number } => {
+            let before = saved.len();
+            saved.retain(|b| b.number != number);
+            if saved.len() == before {
+                print_error(format!("RFC {} is not bookmarked", number));
+                return;
+            }
+            format!("Removed the bookmark for RFC {}.", number)
+        }
+        BookmarkAction::List => {
+            if saved.is_empty() {
+                println!("{}", "No bookmarks yet, add one with `rfcli bookmark add <number>`.".yellow());
+            }
+            for bookmark in &saved {
+                println!("{} {:<6} {}", "RFC".bold(), bookmark.number.to_string().bold().yellow(), bookmark.title);
+            }
+            return;
+        }
+    };
+
+    match bookmarks::save(&path, &saved) {
+        Ok(()) => println!("{}", done.green()),
+        Err(e) => print_error(format!("{}: {}", path.display(), e)),
+    }
+}
+
+fn print_status() {
+    let dir = cache_dir();
+    let index_path = dir.join("rfc-index.txt");
+
+    if json_output() {
+        let mut files = Vec::new();
+        collect_cache_files(&dir, &mut files);
+        let ttl_days = config::get().index_ttl_days.unwrap_or(DEFAULT_INDEX_TTL_DAYS);
+        let age = index_age(&index_path).map(|age| age.as_secs());
+        print_json(&serde_json::json!({
+            "cache_dir": dir,
+            "index_age_secs": age,
+            "index_stale": age.map(|secs| secs > ttl_days * 24 * 60 * 60),
+            "index_mirror": download_sources().get("rfc-index.txt"),
+            "cached_rfcs": cached_rfc_numbers().len(),
+            "files": files.len(),
+            "size_bytes": files.iter().map(|(_, size)| size).sum::<u64>(),
+        }));
+        return;
+    }
+
+    let index_line = match index_age(&index_path) {
+        Some(age) => {
+            let ttl_days = config::get().index_ttl_days.unwrap_or(DEFAULT_INDEX_TTL_DAYS);
+            let age_text = format_age(age);
+            if age.as_secs() > ttl_days * 24 * 60 * 60 {
+                format!("{} {}", age_text, "(stale, refreshed on next use)".yellow())
+            } else {
+                age_text
+            }
+        }
+        None => "not downloaded".dimmed().to_string(),
     };
 
-    let mut child = Command::new(cmd)
-        .args(args)
-        .stdin(Stdio::piped())
-        .spawn()
-        .expect("Failed to spawn pager");
+    let mut files = Vec::new();
+    collect_cache_files(&dir, &mut files);
+    let total_bytes: u64 = files.iter().map(|(_, size)| size).sum();
+
+    println!("{}", "rfcli cache".bold());
+    println!("  {:<14} {}", "Directory:".cyan(), dir.display());
+    println!("  {:<14} {}", "Index updated:".cyan(), index_line);
+    if let Some(mirror) = download_sources().get("rfc-index.txt") {
+        println!("  {:<14} {}", "Index from:".cyan(), mirror);
+    }
+    println!("  {:<14} {}", "Cached RFCs:".cyan(), cached_rfc_numbers().len().to_string().yellow());
+    println!("  {:<14} {} in {} files", "Size on disk:".cyan(), format_size(total_bytes).yellow(), files.len());
+}
+
+/// "just now", "5 minutes ago", "3 days ago"
+fn format_age(age: std::time::Duration) -> String {
+    let secs = age.as_secs();
+    let (value, unit) = match secs {
+        0..=59 => return "just now".to_string(),
+        60..=3599 => (secs / 60, "minute"),
+        3600..=86_399 => (secs / 3600, "hour"),
+        _ => (secs / 86_400, "day"),
+    };
+    format!("{} {}{} ago", value, unit, if value == 1 { "" } else { "s" })
+}
+
+/// Bytes as B/KiB/MiB/GiB with one decimal.
+fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+fn clear_cache(index: bool, all: bool, dry_run: bool) {
+    let dir = cache_dir();
+    if !dir.exists() {
+        println!("{}", "Cache is already empty.".green());
+        return;
+    }
+
+    let rfc_re = Regex::new(r"^rfc\d+\.(?:txt(?:\.gz)?|json)$").unwrap();
+    let mut targets = Vec::new();
+    collect_cache_files(&dir, &mut targets);
+    if !all {
+        targets.retain(|(path, _)| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            path.parent() == Some(dir.as_path())
+                && (rfc_re.is_match(name) || (index && matches!(name, "rfc-index.txt" | "std-index.txt" | "bcp-index.txt" | "fyi-index.txt" | "index-meta.json" | "sources.json" | "rfc-index.json")))
+        });
+    }
+
+    let total_bytes: u64 = targets.iter().map(|(_, size)| size).sum();
+
+    if dry_run {
+        for (path, size) in &targets {
+            println!("  {} ({} bytes)", path.display(), size);
+        }
+        println!("{} {} files, {} bytes would be removed.", "Dry run:".yellow(), targets.len(), total_bytes);
+        return;
+    }
+
+    let mut removed = 0;
+    let mut removed_bytes = 0;
+    if all {
+        match fs::remove_dir_all(&dir) {
+            Ok(()) => {
+                removed = targets.len();
+                removed_bytes = total_bytes;
+            }
+            Err(e) => print_error(format!("{}: {}", dir.display(), e)),
+        }
+    } else {
+        for (path, size) in &targets {
+            match fs::remove_file(path) {
+                Ok(()) => {
+                    removed += 1;
+                    removed_bytes += size;
+                }
+                Err(e) => print_error(format!("{}: {}", path.display(), e)),
+            }
+        }
+    }
+
+    println!("{} {} files, {} bytes.", "Removed".green(), removed, removed_bytes);
+}
 
-    if let Some(mut stdin) = child.stdin.take() {
-        let _ = stdin.write_all(content.as_bytes());
+fn collect_cache_files(dir: &std::path::Path, out: &mut Vec<(PathBuf, u64)>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(meta) = entry.metadata() else { continue };
+        if meta.is_dir() {
+            collect_cache_files(&path, out);
+        } else {
+            out.push((path, meta.len()));
+        }
+    }
+}
+
+/// Where `view_in_pager` opens a document, in less-based pagers.
+enum Jump<'a> {
+    /// At the first match of a less search pattern, every match highlighted
+    Pattern(&'a str),
+    /// At this line, counting from 1
+    Line(usize),
+}
+
+/// Page `content`, trying the `--pager` override, then `$PAGER`, then bat/less.
+/// With a `jump`, less-based pagers open there.
+fn view_in_pager(content: &str, pager: Option<&str>, jump: Option<Jump>) {
+    let jump = jump.map(|jump| match jump {
+        Jump::Pattern(pattern) => format!("+/{}", pattern),
+        Jump::Line(line) => format!("+{}g", line),
+    });
+    // Already colored (a diff) rather than plain RFC text: no man highlighting
+    // from bat on top, and less has to pass the escape codes through
+    let ansi = content.contains('\x1b');
+    let mut candidates: Vec<Vec<String>> = Vec::new();
+    let env_pager = std::env::var("PAGER").ok();
+    for custom in [pager, env_pager.as_deref()].into_iter().flatten() {
+        let mut parts: Vec<String> = custom.split_whitespace().map(String::from).collect();
+        if parts.is_empty() {
+            continue;
+        }
+        let is_less = std::path::Path::new(&parts[0]).file_name().is_some_and(|name| name == "less");
+        if is_less && ansi {
+            parts.push("-R".to_string());
+        }
+        if let (true, Some(jump)) = (is_less, &jump) {
+            parts.push(jump.clone());
+        }
+        candidates.push(parts);
+    }
+    if !ansi && Command::new("bat").arg("--version").stdout(Stdio::null()).status().is_ok() {
+        // We use the 'man' language and 'plain' flags for those nice colors.
+        // bat hands its --pager string to a shell-words parser, so quote the jump.
+        let less = match &jump {
+            Some(jump) => format!("less -FK '{}'", jump.replace('\'', r"'\''")),
+            None => "less -FK".to_string(),
+        };
+        candidates.push(vec!["bat".into(), "-l".into(), "man".into(), "-p".into(), "--pager".into(), less]);
+    }
+    let mut less = vec!["less".to_string(), if ansi { "-FRK" } else { "-FK" }.to_string()];
+    less.extend(jump);
+    candidates.push(less);
+
+    for cmd in &candidates {
+        let paged = interrupt::with_child(|| {
+            let Ok(mut child) = Command::new(&cmd[0])
+                .args(&cmd[1..])
+                .stdin(Stdio::piped())
+                .spawn()
+            else {
+                return false; // Not installed or not executable, try the next one
+            };
+
+            if let Some(mut stdin) = child.stdin.take() {
+                let _ = stdin.write_all(content.as_bytes());
+            }
+
+            let _ = child.wait();
+            true
+        });
+        if paged {
+            return;
+        }
     }
 
-    let _ = child.wait();
+    // Minimal containers may ship neither bat nor less, plain output is better than nothing
+    eprintln!("{}: no pager found (tried $PAGER, bat and less), printing to stdout", "Warning".yellow());
+    let _ = std::io::stdout().write_all(content.as_bytes());
 }
@@ -0,0 +1,175 @@
+use crate::sections;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// "1.  Introduction ......... 5" -> "1.  Introduction"
+static LEADER_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s*\.{4,}\s*\d*\s*$").unwrap());
+/// "[RFC2119]  Bradner, S., ..." opens a reference list entry.
+static REFERENCE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\[[^\]\s]+\]\s{2,}\S").unwrap());
+
+/// Turn a cleaned RFC text into Markdown. Numbered sections become headings,
+/// figures and tables are fenced, prose is dedented so Markdown doesn't take
+/// it for code, and table-of-contents and reference lines keep their own lines.
+pub fn to_markdown(text: &str, number: u32, title: Option<&str>) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let headings: HashMap<usize, sections::Section> = sections::parse_sections(text)
+        .into_iter()
+        .map(|section| (section.start, section))
+        .collect();
+
+    let mut out = match title {
+        Some(title) => format!("# RFC {}: {}\n\n", number, title),
+        None => format!("# RFC {}\n\n", number),
+    };
+    let mut in_fence = false;
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+
+        if in_fence {
+            // A figure keeps going across blank lines as long as another figure line follows
+            let next = lines[i..].iter().find(|l| !l.trim().is_empty());
+            if line.trim().is_empty() && next.is_some_and(|l| crate::is_figure_line(l) && l.starts_with(' ')) {
+                out.push('\n');
+                i += 1;
+                continue;
+            }
+            if !line.trim().is_empty() && crate::is_figure_line(line) {
+                out.push_str(line.trim_end());
+                out.push('\n');
+                i += 1;
+                continue;
+            }
+            out.push_str("```\n");
+            in_fence = false;
+        }
+
+        if line.trim().is_empty() {
+            blank_line(&mut out);
+        } else if let Some(section) = headings.get(&i) {
+            let level = "#".repeat((section.depth + 1).min(6));
+            blank_line(&mut out);
+            out.push_str(&format!("{} {}. {}\n\n", level, section.number, section.title));
+        } else if LEADER_RE.is_match(line) {
+            let entry = LEADER_RE.replace(line.trim(), "");
+            // Nest by section depth: "1.1. Requirements Notation" sits under "1. Introduction"
+            let depth = entry.split_whitespace().next().map(|n| n.trim_end_matches('.').split('.').count()).unwrap_or(1);
+            out.push_str(&format!("{}- {}\n", "  ".repeat(depth - 1), entry));
+        } else if !line.starts_with(' ') && line.len() < 70 && !line.ends_with('.') && !crate::is_figure_line(line) {
+            // Unnumbered headings in column 0: "Abstract", "Authors' Addresses", ...
+            blank_line(&mut out);
+            out.push_str(&format!("## {}\n\n", line.trim()));
+        } else if crate::is_figure_line(line) {
+            blank_line(&mut out);
+            out.push_str("```\n");
+            out.push_str(line.trim_end());
+            out.push('\n');
+            in_fence = true;
+        } else {
+            let body = line.trim();
+            // "[RFC2119]  Bradner, S., ..." starts a new reference, "o  item" a bullet
+            let starts_item = REFERENCE_RE.is_match(body) || body.starts_with("o ");
+            if starts_item {
+                blank_line(&mut out);
+            }
+            match body.strip_prefix("o ") {
+                Some(item) => out.push_str(&format!("- {}\n", item.trim_start())),
+                None => {
+                    out.push_str(body);
+                    out.push('\n');
+                }
+            }
+        }
+        i += 1;
+    }
+
+    if in_fence {
+        out.push_str("```\n");
+    }
+    out.trim_end().to_string() + "\n"
+}
+
+/// End `out` with exactly one blank line, so blocks don't run together.
+fn blank_line(out: &mut String) {
+    while !out.ends_with("\n\n") {
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_headings_contents_figures_and_lists() {
+        let text = "Abstract
+
+   This document defines a protocol.
+
+Table of Contents
+
+   1.  Introduction ................................................  2
+     1.1.  Terminology .............................................  3
+
+1.  Introduction
+
+   The exchange looks like this:
+
+      Client                Server
+        |  ---- Hello --->  |
+
+        |  <--- Hello ----  |
+
+   It has two properties:
+
+   o  it is short
+   o  it is symmetric
+
+1.1.  Terminology
+
+   [RFC2119]  Bradner, S., \"Key words for use in RFCs\", March 1997.
+";
+        assert_eq!(
+            to_markdown(text, 9999, Some("A Protocol")),
+            "# RFC 9999: A Protocol
+
+## Abstract
+
+This document defines a protocol.
+
+## Table of Contents
+
+- 1.  Introduction
+  - 1.1.  Terminology
+
+## 1. Introduction
+
+The exchange looks like this:
+
+```
+      Client                Server
+        |  ---- Hello --->  |
+
+        |  <--- Hello ----  |
+```
+
+It has two properties:
+
+- it is short
+
+- it is symmetric
+
+### 1.1. Terminology
+
+[RFC2119]  Bradner, S., \"Key words for use in RFCs\", March 1997.
+"
+        );
+    }
+
+    #[test]
+    fn untitled_documents_get_a_bare_heading() {
+        assert_eq!(to_markdown("   Some text.\n", 1, None), "# RFC 1\n\nSome text.\n");
+    }
+}
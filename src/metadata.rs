@@ -0,0 +1,87 @@
+use crate::index::RfcEntry;
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use std::sync::LazyLock;
+
+static TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+/// What rfc-editor.org publishes about an RFC at `/rfc/rfc{n}.json`, the
+/// fields rfcli shows. Missing (or null) fields are left empty.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Metadata {
+    /// "RFC9110"
+    #[serde(deserialize_with = "or_default")]
+    pub doc_id: String,
+    #[serde(deserialize_with = "or_default")]
+    pub title: String,
+    /// "R. Fielding, Ed.", as in the index
+    #[serde(deserialize_with = "or_default")]
+    pub authors: Vec<String>,
+    /// "June 2022"
+    #[serde(deserialize_with = "or_default")]
+    pub pub_date: String,
+    /// The status now, which may differ from `pub_status` at publication
+    #[serde(deserialize_with = "or_default")]
+    pub status: String,
+    #[serde(deserialize_with = "or_default")]
+    pub pub_status: String,
+    /// Paragraphs, in HTML for the newer RFCs
+    #[serde(rename = "abstract", deserialize_with = "or_default")]
+    pub abstract_html: String,
+    /// ["RFC2818", ...]
+    #[serde(deserialize_with = "or_default")]
+    pub obsoletes: Vec<String>,
+    #[serde(deserialize_with = "or_default")]
+    pub obsoleted_by: Vec<String>,
+    #[serde(deserialize_with = "or_default")]
+    pub updates: Vec<String>,
+    #[serde(deserialize_with = "or_default")]
+    pub updated_by: Vec<String>,
+}
+
+impl Metadata {
+    /// The same facts as an index entry, for everything that shows one.
+    pub fn to_entry(&self, number: u32) -> RfcEntry {
+        let status = if self.status.trim().is_empty() { &self.pub_status } else { &self.status };
+        RfcEntry {
+            number,
+            title: self.title.trim().to_string(),
+            authors: self.authors.iter().map(|a| a.trim().to_string()).filter(|a| !a.is_empty()).collect(),
+            date: self.pub_date.trim().to_string(),
+            status: status.trim().to_ascii_uppercase(),
+            obsoletes: rfc_numbers(&self.obsoletes),
+            obsoleted_by: rfc_numbers(&self.obsoleted_by),
+            updates: rfc_numbers(&self.updates),
+            updated_by: rfc_numbers(&self.updated_by),
+        }
+    }
+
+    /// The abstract as plain text, paragraphs separated by a blank line.
+    /// None when the metadata has none.
+    pub fn abstract_text(&self) -> Option<String> {
+        let html = self.abstract_html.replace("</p>", "\n\n");
+        let paragraphs: Vec<String> = TAG_RE
+            .replace_all(&html, "")
+            .split("\n\n")
+            .map(|paragraph| crate::html::decode(&paragraph.split_whitespace().collect::<Vec<_>>().join(" ")))
+            .filter(|paragraph| !paragraph.is_empty())
+            .collect();
+        (!paragraphs.is_empty()).then(|| paragraphs.join("\n\n"))
+    }
+}
+
+/// 2818 for "RFC2818" or "RFC 2818". Other documents (BCPs, STDs) are left out.
+fn rfc_numbers(ids: &[String]) -> Vec<u32> {
+    ids.iter()
+        .filter_map(|id| {
+            let id = id.trim();
+            let digits = id.get(..3).filter(|prefix| prefix.eq_ignore_ascii_case("rfc")).map(|_| id[3..].trim())?;
+            digits.parse().ok()
+        })
+        .collect()
+}
+
+fn or_default<'de, D: Deserializer<'de>, T: Default + Deserialize<'de>>(deserializer: D) -> Result<T, D::Error> {
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
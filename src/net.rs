@@ -0,0 +1,144 @@
+use crate::config;
+use colored::Colorize;
+use reqwest::header::HeaderMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Default timeout for connecting, for the response headers and for each
+/// chunk of a download. A slow but steady body takes as long as it needs.
+const DEFAULT_TIMEOUT_SECS: u64 = 15;
+/// Default number of retries after the first failed attempt.
+const DEFAULT_RETRIES: u32 = 3;
+/// Where rfc-editor.org keeps the index files and RFC texts, the last mirror tried.
+pub const RFC_EDITOR: &str = "https://www.rfc-editor.org/rfc";
+/// Identifies us to rfc-editor.org unless the config file says otherwise.
+const DEFAULT_USER_AGENT: &str = concat!("rfcli/", env!("CARGO_PKG_VERSION"), " (+https://github.com/ponchorolls/rfcli)");
+
+static CLIENT: OnceLock<Result<reqwest::Client, String>> = OnceLock::new();
+
+pub(crate) fn timeout() -> Duration {
+    Duration::from_secs(config::get().timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS))
+}
+
+fn retries() -> u32 {
+    config::get().retries.unwrap_or(DEFAULT_RETRIES)
+}
+
+/// The process-wide async client. It only has a connect timeout so long
+/// model generations aren't cut off; downloads add one for the headers via `get`.
+/// Built once, a `proxy` or `user_agent` in the config that reqwest rejects
+/// is an `Error::Config` for every request rather than a client without them.
+pub fn client() -> Result<&'static reqwest::Client, crate::Error> {
+    CLIENT
+        .get_or_init(|| {
+            let user_agent = config::get().user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT);
+            let mut builder = reqwest::Client::builder()
+                .connect_timeout(timeout())
+                .user_agent(user_agent);
+            // Without a configured proxy reqwest picks up HTTP_PROXY/HTTPS_PROXY/NO_PROXY itself
+            if let Some(url) = config::get().proxy.as_deref() {
+                let proxy = reqwest::Proxy::all(url).map_err(|e| format!("the proxy '{}' in the config is not usable: {}", url, e))?;
+                builder = builder.proxy(proxy.no_proxy(reqwest::NoProxy::from_env()));
+            }
+            builder.build().map_err(|e| format!("could not set up the HTTP client: {}", e))
+        })
+        .as_ref()
+        .map_err(|message| crate::Error::Config(message.clone()))
+}
+
+fn uses_proxy() -> bool {
+    config::get().proxy.is_some()
+        || ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"]
+            .iter()
+            .any(|var| std::env::var_os(var).is_some_and(|v| !v.is_empty()))
+}
+
+/// Timeouts, failed connections and 5xx answers are worth another try. 4xx
+/// answers and requests that could not be built or redirected are not.
+fn is_transient(result: &Result<reqwest::StatusCode, &reqwest::Error>) -> bool {
+    match result {
+        Ok(status) => status.is_server_error(),
+        Err(e) => e.is_timeout() || e.is_connect(),
+    }
+}
+
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_millis(500 * 2u64.pow(attempt))
+}
+
+/// GET with a timeout for the response headers, retrying transient failures
+/// with exponential backoff. Reading the body is up to the caller.
+pub async fn get(url: &str) -> Result<reqwest::Response, crate::Error> {
+    get_with_retries(url, HeaderMap::new(), true).await
+}
+
+/// The configured `mirrors` in order, then rfc-editor.org.
+pub fn mirrors() -> Vec<String> {
+    let mut bases: Vec<String> = config::get()
+        .mirrors
+        .iter()
+        .map(|base| base.trim().trim_end_matches('/').to_string())
+        .filter(|base| !base.is_empty())
+        .collect();
+    if !bases.iter().any(|base| base == RFC_EDITOR) {
+        bases.push(RFC_EDITOR.to_string());
+    }
+    bases
+}
+
+/// `get` of `path` ("rfc2616.txt", "rfc-index.txt") from the first mirror that
+/// has it, along with the base URL that served it. A mirror that can't be
+/// reached or answers anything but 2xx or 304 is skipped, when every one fails
+/// the last answer is returned.
+pub async fn get_mirrored(path: &str, headers: HeaderMap, report: bool) -> Result<(reqwest::Response, String), crate::Error> {
+    let bases = mirrors();
+    let mut last = Err(crate::Error::Http(format!("no mirror to download {} from", path)));
+    for (i, base) in bases.iter().enumerate() {
+        let result = get_with_retries(&format!("{}/{}", base, path), headers.clone(), report).await;
+        let served = matches!(&result, Ok(response) if response.status().is_success() || response.status() == reqwest::StatusCode::NOT_MODIFIED);
+        if let (false, true, Some(next)) = (served, report, bases.get(i + 1)) {
+            let reason = match &result {
+                Ok(response) => response.status().to_string(),
+                Err(e) => e.to_string(),
+            };
+            notice!("{}", format!("{} failed ({}), trying {}...", base, reason, next).dimmed());
+        }
+        last = result.map(|response| (response, base.clone()));
+        if served {
+            break;
+        }
+    }
+    last
+}
+
+async fn get_with_retries(url: &str, headers: HeaderMap, report: bool) -> Result<reqwest::Response, crate::Error> {
+    let retries = retries();
+    let mut attempt = 0;
+    loop {
+        // A deadline on the whole request would cut off large RFCs on slow links
+        let sent = tokio::time::timeout(timeout(), client()?.get(url).headers(headers.clone()).send()).await;
+        let transient = match &sent {
+            Ok(result) => is_transient(&result.as_ref().map(|r| r.status())),
+            Err(_) => true,
+        };
+        if !transient || attempt >= retries {
+            let mut message = match sent {
+                Ok(Ok(response)) => return Ok(response),
+                Ok(Err(e)) if e.is_connect() && uses_proxy() => format!("{} (is the proxy reachable?)", e),
+                Ok(Err(e)) => e.to_string(),
+                Err(_) => format!("{} did not answer within {:?}", url, timeout()),
+            };
+            if attempt > 0 {
+                message.push_str(&format!(" (gave up after {} attempts)", attempt + 1));
+            }
+            return Err(crate::Error::Http(message));
+        }
+
+        if report {
+            notice!("{}", format!("Request failed, retrying in {:?}...", backoff(attempt)).dimmed());
+        }
+        tokio::time::sleep(backoff(attempt)).await;
+        attempt += 1;
+    }
+}
+
@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Where reading stopped in each document ("rfc2616"), as the byte offset
+/// of the top line on screen. Empty when the file is missing or unreadable.
+pub fn load(path: &Path) -> BTreeMap<String, u64> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(path: &Path, positions: &BTreeMap<String, u64>) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    rfcli::write_atomic(path, serde_json::to_string_pretty(positions)?)
+}
+
+/// The last position less saved for `file` in its history file, which it
+/// only writes with `--save-marks`. Mark lines read `m ' 1 360 /path/file`:
+/// the mark, its screen line, the byte offset and the file name.
+pub fn last_mark(history: &str, file: &Path) -> Option<u64> {
+    let file = file.to_str()?;
+    history
+        .lines()
+        .rev()
+        .find_map(|line| {
+            let mut fields = line.splitn(5, ' ');
+            match (fields.next(), fields.next(), fields.next(), fields.next(), fields.next()) {
+                (Some("m"), Some("'"), Some(_), Some(offset), Some(name)) if name == file => offset.parse().ok(),
+                _ => None,
+            }
+        })
+}
+
+/// `history` without the marks of files that no longer exist, the
+/// temporary files `page_resuming` reads are gone once less quits.
+pub fn prune_marks(history: &str) -> String {
+    history
+        .lines()
+        .filter(|line| {
+            let mut fields = line.splitn(5, ' ');
+            match (fields.next(), fields.next(), fields.next(), fields.next(), fields.next()) {
+                (Some("m"), Some(_), Some(_), Some(_), Some(name)) => Path::new(name).exists(),
+                _ => true,
+            }
+        })
+        .flat_map(|line| [line, "\n"])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prunes_marks_of_removed_files() {
+        let kept = std::env::current_exe().unwrap();
+        let kept = kept.display();
+        let history = format!(
+            ".less-history-file:\n.search\n\"RFC\n.mark\nm ' 1 360 /nonexistent/rfcli-rfc2616-1234.txt\nm ' 1 720 {}\n",
+            kept
+        );
+        let pruned = prune_marks(&history);
+        assert_eq!(pruned, format!(".less-history-file:\n.search\n\"RFC\n.mark\nm ' 1 720 {}\n", kept));
+        assert_eq!(last_mark(&history, Path::new("/nonexistent/rfcli-rfc2616-1234.txt")), Some(360));
+        assert_eq!(last_mark(&pruned, Path::new("/nonexistent/rfcli-rfc2616-1234.txt")), None);
+    }
+}
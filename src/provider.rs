@@ -0,0 +1,555 @@
+use crate::config;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+/// How often a rate-limited (429) request is retried before giving up.
+const RATE_LIMIT_RETRIES: u32 = 3;
+/// Longer `Retry-After` waits than this are reported instead of slept through.
+const MAX_RETRY_AFTER_SECS: u64 = 60;
+
+/// Chat models currently served by Groq's OpenAI-compatible endpoint.
+pub const GROQ_MODELS: &[&str] = &[
+    "llama-3.1-8b-instant",
+    "llama-3.3-70b-versatile",
+    "meta-llama/llama-4-scout-17b-16e-instruct",
+    "meta-llama/llama-4-maverick-17b-128e-instruct",
+    "openai/gpt-oss-20b",
+    "openai/gpt-oss-120b",
+    "moonshotai/kimi-k2-instruct",
+    "qwen/qwen3-32b",
+    "gemma2-9b-it",
+];
+
+/// How long a listed set of models is reused before `models` asks again.
+pub const MODELS_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Where summaries come from. All of these speak the OpenAI chat-completions schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    #[default]
+    Groq,
+    Openai,
+    /// Any OpenAI-compatible server (vLLM, LocalAI, ...), needs --base-url
+    Custom,
+    /// A local Ollama daemon, no API key needed
+    Ollama,
+}
+
+impl Provider {
+    pub fn name(self) -> &'static str {
+        match self {
+            Provider::Groq => "Groq Cloud",
+            Provider::Openai => "OpenAI",
+            Provider::Custom => "custom endpoint",
+            Provider::Ollama => "Ollama",
+        }
+    }
+
+    pub fn default_base_url(self) -> Option<&'static str> {
+        match self {
+            Provider::Groq => Some("https://api.groq.com/openai/v1"),
+            Provider::Openai => Some("https://api.openai.com/v1"),
+            Provider::Custom => None,
+            Provider::Ollama => Some("http://localhost:11434"),
+        }
+    }
+
+    pub fn default_model(self) -> &'static str {
+        match self {
+            Provider::Groq => "llama-3.1-8b-instant",
+            Provider::Openai => "gpt-4o-mini",
+            Provider::Custom => "default",
+            Provider::Ollama => "llama3",
+        }
+    }
+
+    /// Environment variable the API key is read from unless `api_key_env` overrides it.
+    pub fn api_key_env(self) -> &'static str {
+        match self {
+            Provider::Groq => "GROQ_API_KEY",
+            Provider::Openai => "OPENAI_API_KEY",
+            Provider::Custom | Provider::Ollama => "RFCLI_API_KEY",
+        }
+    }
+
+    /// Self-hosted servers frequently run without authentication.
+    pub fn requires_api_key(self) -> bool {
+        matches!(self, Provider::Groq | Provider::Openai)
+    }
+}
+
+/// Tokens billed for one or more chat requests, as reported by the server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Usage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl Usage {
+    pub fn total(self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    /// OpenAI-style `usage` objects, and Ollama's `prompt_eval_count`/`eval_count`.
+    fn parse(v: &serde_json::Value) -> Option<Usage> {
+        let openai = v["usage"].as_object().or_else(|| v["x_groq"]["usage"].as_object());
+        if let Some(usage) = openai {
+            return Some(Usage {
+                prompt_tokens: usage.get("prompt_tokens").and_then(|n| n.as_u64()).unwrap_or(0),
+                completion_tokens: usage.get("completion_tokens").and_then(|n| n.as_u64()).unwrap_or(0),
+            });
+        }
+        if v["prompt_eval_count"].is_u64() || v["eval_count"].is_u64() {
+            return Some(Usage {
+                prompt_tokens: v["prompt_eval_count"].as_u64().unwrap_or(0),
+                completion_tokens: v["eval_count"].as_u64().unwrap_or(0),
+            });
+        }
+        None
+    }
+}
+
+impl std::ops::Add for Usage {
+    type Output = Usage;
+
+    fn add(self, other: Usage) -> Usage {
+        Usage {
+            prompt_tokens: self.prompt_tokens + other.prompt_tokens,
+            completion_tokens: self.completion_tokens + other.completion_tokens,
+        }
+    }
+}
+
+/// The text of a reply, plus its token counts when the server reported them.
+pub struct Reply {
+    pub text: String,
+    pub usage: Option<Usage>,
+}
+
+/// A fully resolved place to send chat requests to.
+pub struct Endpoint {
+    pub provider: Provider,
+    pub base_url: String,
+    pub api_key: Option<String>,
+    key_env: String,
+    pub model: String,
+}
+
+impl Endpoint {
+    /// Merge CLI flags over the config file over the provider defaults.
+    /// A missing API key is only reported by `require_api_key`, cached summaries don't need one.
+    pub fn resolve(
+        provider: Option<Provider>,
+        base_url: Option<String>,
+        model: Option<String>,
+        api_key: Option<String>,
+    ) -> Result<Endpoint, crate::Error> {
+        let cfg = config::get();
+        let provider = provider.or(cfg.provider).unwrap_or_default();
+
+        let Some(base_url) = base_url
+            .or_else(|| cfg.base_url.clone())
+            .or_else(|| provider.default_base_url().map(String::from))
+        else {
+            return Err(crate::Error::Config("the custom provider needs --base-url (or base_url in the config file)".into()));
+        };
+
+        let model = model
+            .or_else(|| cfg.default_model.clone())
+            .unwrap_or_else(|| provider.default_model().to_string());
+        // Models the `models` command has seen served count as known, new ones included
+        let listed = cached_models(&base_url).map(|(models, _)| models).unwrap_or_default();
+        if provider == Provider::Groq && !GROQ_MODELS.contains(&model.as_str()) && !listed.contains(&model) {
+            let known = if listed.is_empty() { GROQ_MODELS.iter().map(|m| m.to_string()).collect() } else { listed };
+            return Err(crate::Error::UnknownModel { provider, model, known });
+        }
+
+        let key_env = cfg.api_key_env.clone().unwrap_or_else(|| provider.api_key_env().to_string());
+        let api_key = api_key
+            .or_else(|| std::env::var(&key_env).ok())
+            .or_else(|| cfg.api_key.clone())
+            .or_else(|| if provider == Provider::Groq { cfg.groq_api_key.clone() } else { None })
+            .filter(|key| !key.trim().is_empty());
+
+        Ok(Endpoint {
+            provider,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            key_env,
+            model,
+        })
+    }
+
+    /// `Error::NoApiKey` when the provider needs a key and none is configured.
+    pub fn require_api_key(&self) -> Result<(), crate::Error> {
+        if self.api_key.is_none() && self.provider.requires_api_key() {
+            return Err(crate::Error::NoApiKey { provider: self.provider, key_env: self.key_env.clone() });
+        }
+        Ok(())
+    }
+
+    /// "groq llama-3.1-8b-instant at https://..., key gsk_…Wx3f", for diagnostics.
+    /// Only the end of the API key is shown.
+    pub fn describe(&self) -> String {
+        let key = match &self.api_key {
+            Some(key) => format!("key {}", redact(key)),
+            None => "no key".to_string(),
+        };
+        format!("{} {} at {}, {}", self.provider.name(), self.model, self.base_url, key)
+    }
+
+    /// One round trip to the chat endpoint.
+    pub async fn chat(&self, client: &reqwest::Client, system: &str, user: &str) -> Result<Reply, crate::Error> {
+        if self.provider == Provider::Ollama {
+            return self.ollama_chat(client, system, user).await;
+        }
+
+        let mut request = client
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&json!({
+                "model": self.model,
+                "messages": [
+                    { "role": "system", "content": system },
+                    { "role": "user", "content": user }
+                ]
+            }));
+        if let Some(key) = &self.api_key {
+            request = request.header("Authorization", format!("Bearer {}", key));
+        }
+
+        let response = self.send(request).await?;
+        let status = response.status();
+        let body = response.text().await.map_err(|e| crate::Error::Http(format!("Network Error: reading the response failed: {}", e)))?;
+        if !status.is_success() {
+            return Err(self.http_error(status, &body));
+        }
+        let v: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| crate::Error::Api(format!("{} sent a reply that isn't valid JSON ({}): {}", self.provider.name(), e, excerpt(&body))))?;
+        if let Some(message) = api_error_message(&v) {
+            return Err(crate::Error::Api(format!("{} API error: {}", self.provider.name(), message)));
+        }
+
+        match v["choices"][0]["message"]["content"].as_str() {
+            Some(summary_text) => Ok(Reply { text: summary_text.to_string(), usage: Usage::parse(&v) }),
+            None => Err(crate::Error::Api(format!("API response did not contain a summary.\nDebug: {}", body))),
+        }
+    }
+
+    /// IDs of the models the server offers, sorted: `/models` on OpenAI-style
+    /// servers, `/api/tags` (the pulled models) on Ollama.
+    pub async fn list_models(&self, client: &reqwest::Client) -> Result<Vec<String>, crate::Error> {
+        let (path, list, field) = match self.provider {
+            Provider::Ollama => ("api/tags", "models", "name"),
+            _ => ("models", "data", "id"),
+        };
+        let mut request = client.get(format!("{}/{}", self.base_url, path));
+        if let Some(key) = &self.api_key {
+            request = request.header("Authorization", format!("Bearer {}", key));
+        }
+
+        let response = self.send(request).await?;
+        let status = response.status();
+        let body = response.text().await.map_err(|e| crate::Error::Http(format!("Network Error: reading the response failed: {}", e)))?;
+        if !status.is_success() {
+            return Err(self.http_error(status, &body));
+        }
+        let v: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| crate::Error::Api(format!("{} sent a reply that isn't valid JSON ({}): {}", self.provider.name(), e, excerpt(&body))))?;
+        let Some(models) = v[list].as_array() else {
+            return Err(crate::Error::Api(format!("{} sent no model list: {}", self.provider.name(), excerpt(&body))));
+        };
+        let mut ids: Vec<String> = models.iter().filter_map(|m| m[field].as_str().map(String::from)).collect();
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Like `chat`, but asks for a streamed reply and calls `on_token` with each
+    /// piece of text as it arrives. Returns the whole reply at the end.
+    /// `max_tokens` caps the reply length when set. `include_usage` asks
+    /// OpenAI-style servers to send token counts at the end of the stream.
+    pub async fn chat_stream<F: FnMut(&str)>(
+        &self,
+        client: &reqwest::Client,
+        system: &str,
+        user: &str,
+        max_tokens: Option<u32>,
+        include_usage: bool,
+        mut on_token: F,
+    ) -> Result<Reply, crate::Error> {
+        let ollama = self.provider == Provider::Ollama;
+        let url = if ollama {
+            format!("{}/api/chat", self.base_url)
+        } else {
+            format!("{}/chat/completions", self.base_url)
+        };
+        let mut body = json!({
+            "model": self.model,
+            "stream": true,
+            "messages": [
+                { "role": "system", "content": system },
+                { "role": "user", "content": user }
+            ]
+        });
+        // Ollama calls the reply budget num_predict
+        if let Some(max_tokens) = max_tokens {
+            if ollama {
+                body["options"] = json!({ "num_predict": max_tokens });
+            } else {
+                body["max_tokens"] = json!(max_tokens);
+            }
+        }
+        // Ollama always reports its counts in the final line
+        if include_usage && !ollama {
+            body["stream_options"] = json!({ "include_usage": true });
+        }
+        let mut request = client.post(url).json(&body);
+        if let Some(key) = self.api_key.as_ref().filter(|_| !ollama) {
+            request = request.header("Authorization", format!("Bearer {}", key));
+        }
+
+        let mut response = self.send(request).await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(self.http_error(status, &body));
+        }
+
+        // Bytes are buffered until a full line is in, so a multibyte character
+        // split across two network chunks is only decoded once it is complete.
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut reply = String::new();
+        let mut usage = None;
+        let mut unparsed = String::new();
+        loop {
+            let chunk = response.chunk().await.map_err(|e| crate::Error::Http(format!("Network Error: {}", e)))?;
+            let done = chunk.is_none();
+            if let Some(bytes) = chunk {
+                buffer.extend_from_slice(&bytes);
+            } else if !buffer.is_empty() {
+                buffer.push(b'\n'); // Flush a final unterminated line
+            }
+
+            while let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=pos).collect();
+                let line = String::from_utf8_lossy(&line);
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+
+                // OpenAI-style servers send SSE `data: {...}` lines, Ollama sends bare JSON lines
+                let payload = line.strip_prefix("data:").map(str::trim).unwrap_or(line);
+                if payload == "[DONE]" {
+                    continue;
+                }
+                let v: serde_json::Value = match serde_json::from_str(payload) {
+                    Ok(v) => v,
+                    Err(_) => {
+                        unparsed.push_str(line);
+                        continue;
+                    }
+                };
+                // Both kinds of server report failures mid-stream as an `error` object
+                if let Some(message) = api_error_message(&v) {
+                    return Err(crate::Error::Api(format!("{} API error: {}", self.provider.name(), message)));
+                }
+                // The usage chunk comes last, with an empty `choices` list
+                if let Some(counts) = Usage::parse(&v) {
+                    usage = Some(counts);
+                    if v["choices"].as_array().is_some_and(|choices| choices.is_empty()) {
+                        continue;
+                    }
+                }
+                let token = if ollama {
+                    v["message"]["content"].as_str()
+                } else {
+                    v["choices"][0]["delta"]["content"].as_str()
+                };
+                match token {
+                    Some(token) if !token.is_empty() => {
+                        on_token(token);
+                        reply.push_str(token);
+                    }
+                    Some(_) => {}
+                    None => unparsed.push_str(line),
+                }
+            }
+
+            if done {
+                break;
+            }
+        }
+
+        if reply.trim().is_empty() {
+            Err(crate::Error::Api(format!("API response did not contain a summary.\nDebug: {}", unparsed)))
+        } else {
+            Ok(Reply { text: reply, usage })
+        }
+    }
+
+    /// Send a chat request, waiting out 429 responses as long as the server's
+    /// `Retry-After` is short enough and we haven't retried too often already.
+    async fn send(&self, mut request: reqwest::RequestBuilder) -> Result<reqwest::Response, crate::Error> {
+        let mut attempt = 0;
+        loop {
+            let retry = request.try_clone();
+            let response = request.send().await.map_err(|e| match self.provider {
+                Provider::Ollama if e.is_connect() => crate::Error::Http(format!("Network Error: {} (is `ollama serve` running?)", e)),
+                _ => crate::Error::Http(format!("Network Error: {}", e)),
+            })?;
+            if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+                return Ok(response);
+            }
+
+            // Without a Retry-After header back off 2s, 4s, 8s, ...
+            let wait = retry_after(&response).unwrap_or(2u64 << attempt);
+            let Some(next) = retry.filter(|_| attempt < RATE_LIMIT_RETRIES && wait <= MAX_RETRY_AFTER_SECS) else {
+                return Err(crate::Error::Api(format!("rate limited by {}, try again in {}s", self.provider.name(), wait)));
+            };
+            notice!("{}", format!("Rate limited by {}, retrying in {}s...", self.provider.name(), wait).yellow());
+            tokio::time::sleep(Duration::from_secs(wait)).await;
+            request = next;
+            attempt += 1;
+        }
+    }
+
+    /// A non-2xx reply, explained by the API's own error message when it sent one.
+    fn http_error(&self, status: reqwest::StatusCode, body: &str) -> crate::Error {
+        let detail = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|v| api_error_message(&v))
+            .unwrap_or_else(|| excerpt(body));
+        let hint = match status.as_u16() {
+            401 | 403 => " (check your API key)",
+            404 => " (check --base-url and the model name)",
+            _ => "",
+        };
+        crate::Error::Api(format!("{} returned HTTP {}{}: {}", self.provider.name(), status, hint, detail))
+    }
+
+    /// Ollama's native `/api/chat`. With `stream: false` the reply is one JSON
+    /// object, but older daemons stream newline-delimited chunks regardless.
+    async fn ollama_chat(&self, client: &reqwest::Client, system: &str, user: &str) -> Result<Reply, crate::Error> {
+        let request = client.post(format!("{}/api/chat", self.base_url)).json(&json!({
+            "model": self.model,
+            "stream": false,
+            "messages": [
+                { "role": "system", "content": system },
+                { "role": "user", "content": user }
+            ]
+        }));
+        let response = self.send(request).await?;
+        let status = response.status();
+        let body = response.text().await.map_err(|e| crate::Error::Http(format!("Network Error: reading the response failed: {}", e)))?;
+        if !status.is_success() {
+            return Err(self.http_error(status, &body));
+        }
+
+        let mut summary = String::new();
+        let mut usage = None;
+        for line in body.lines().filter(|line| !line.trim().is_empty()) {
+            let v: serde_json::Value = serde_json::from_str(line)
+                .map_err(|e| crate::Error::Api(format!("Ollama sent a reply that isn't valid JSON ({}): {}", e, excerpt(line))))?;
+            if let Some(message) = api_error_message(&v) {
+                return Err(crate::Error::Api(format!("Ollama: {}", message)));
+            }
+            if let Some(content) = v["message"]["content"].as_str() {
+                summary.push_str(content);
+            }
+            if let Some(counts) = Usage::parse(&v) {
+                usage = Some(counts);
+            }
+        }
+
+        if summary.trim().is_empty() {
+            Err(crate::Error::Api(format!("API response did not contain a summary.\nDebug: {}", body)))
+        } else {
+            Ok(Reply { text: summary, usage })
+        }
+    }
+}
+
+/// What `models` last listed for each base URL, in `models.json` in the cache.
+#[derive(Default, Serialize, Deserialize)]
+struct ModelCache(std::collections::BTreeMap<String, ListedModels>);
+
+#[derive(Serialize, Deserialize)]
+struct ListedModels {
+    /// Unix time of the listing
+    fetched: u64,
+    models: Vec<String>,
+}
+
+fn model_cache_path() -> std::path::PathBuf {
+    crate::cache_dir().join("models.json")
+}
+
+fn load_model_cache() -> ModelCache {
+    std::fs::read_to_string(model_cache_path())
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// The models last listed for `base_url` and how long ago that was.
+pub fn cached_models(base_url: &str) -> Option<(Vec<String>, Duration)> {
+    let listed = load_model_cache().0.remove(base_url.trim_end_matches('/'))?;
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).ok()?.as_secs();
+    Some((listed.models, Duration::from_secs(now.saturating_sub(listed.fetched))))
+}
+
+pub fn save_models(base_url: &str, models: &[String]) -> std::io::Result<()> {
+    let fetched = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let mut cache = load_model_cache();
+    cache.0.insert(base_url.trim_end_matches('/').to_string(), ListedModels { fetched, models: models.to_vec() });
+    let path = model_cache_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    crate::write_atomic(&path, serde_json::to_string_pretty(&cache)?)
+}
+
+/// OpenAI-style `{"error": {"message": ...}}` or Ollama's `{"error": "..."}`.
+fn api_error_message(v: &serde_json::Value) -> Option<String> {
+    let error = v.get("error").filter(|e| !e.is_null())?;
+    error["message"]
+        .as_str()
+        .or_else(|| error.as_str())
+        .map(String::from)
+        .or_else(|| Some(error.to_string()))
+}
+
+/// The start of a response body, short enough for an error message.
+fn excerpt(body: &str) -> String {
+    let body = body.trim();
+    match body.char_indices().nth(200) {
+        Some((end, _)) => format!("{}...", &body[..end]),
+        None if body.is_empty() => "(empty response)".to_string(),
+        None => body.to_string(),
+    }
+}
+
+/// `Retry-After` in seconds. The HTTP-date form isn't used by any provider we talk to.
+fn retry_after(response: &reqwest::Response) -> Option<u64> {
+    let value = response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    let secs: f64 = value.trim().parse().ok()?;
+    Some(secs.max(0.0).ceil() as u64)
+}
+
+/// "gsk_…Wx3f": the vendor prefix and the last four characters, or just
+/// "…" for keys too short to show any of.
+fn redact(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() < 12 {
+        return "…".to_string();
+    }
+    let prefix = chars.iter().position(|&c| c == '_' || c == '-').filter(|&i| i < 4).map_or(0, |i| i + 1);
+    let head: String = chars[..prefix].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
+}
@@ -0,0 +1,195 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Numbered headings start in column 0: "9.  Security Considerations",
+/// "2.1.  Client/Server Messaging", "Appendix A.  HTTP Version History", "A.1.  Changes".
+static HEADING_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(Appendix [A-Z]|(?:\d{1,3}|[A-Z])(?:\.\d{1,3})*)\.?\s+(\p{Lu}.*?)\s*$").unwrap()
+});
+/// Table of contents lines ("1. Introduction ......... 5") are not headings.
+static LEADER_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\.{4,}\s*\d*$").unwrap());
+
+/// Unnumbered sections every RFC carries, whatever it's about.
+const BOILERPLATE: &[&str] = &[
+    "status of this memo",
+    "copyright notice",
+    "table of contents",
+    "iesg note",
+    "full copyright statement",
+    "intellectual property",
+    "intellectual property statement",
+];
+
+/// One numbered section of an RFC, as a range of lines of the text it was parsed from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// "9", "2.1", "A", "A.1"
+    pub number: String,
+    pub title: String,
+    /// Nesting level, 1 for top-level sections
+    pub depth: usize,
+    /// Line index of the heading
+    pub start: usize,
+    /// Line index one past the last line, including subsections
+    pub end: usize,
+}
+
+/// Find every numbered heading of a (cleaned) RFC text.
+pub fn parse_sections(text: &str) -> Vec<Section> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut sections: Vec<Section> = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        if LEADER_RE.is_match(line) {
+            continue;
+        }
+        let Some(caps) = HEADING_RE.captures(line) else { continue };
+        let number = caps[1].trim_start_matches("Appendix ").to_string();
+        // A lone capital letter is only a heading when it's spelled as an appendix
+        if number.len() == 1 && number.starts_with(|c: char| c.is_ascii_uppercase()) && !caps[1].starts_with("Appendix") {
+            continue;
+        }
+        let depth = number.split('.').count();
+        sections.push(Section { number, title: caps[2].to_string(), depth, start: i, end: lines.len() });
+    }
+
+    // A section runs until the next heading at the same or a shallower level
+    for i in 0..sections.len() {
+        let depth = sections[i].depth;
+        if let Some(next) = sections[i + 1..].iter().find(|s| s.depth <= depth) {
+            sections[i].end = next.start;
+        }
+    }
+
+    sections
+}
+
+/// The text without the boilerplate sections ("Status of This Memo",
+/// "Copyright Notice", ...) and the table of contents, for spending a
+/// model's context on what the RFC actually says. Each of them runs from
+/// its heading to the next line in column 0 that isn't a contents entry.
+pub fn strip_boilerplate(text: &str) -> String {
+    let mut out = String::new();
+    let mut skipping = false;
+    for line in text.lines() {
+        let column_zero = !line.trim().is_empty() && !line.starts_with(char::is_whitespace);
+        if column_zero && !(skipping && LEADER_RE.is_match(line)) {
+            let title = line.trim().trim_end_matches('.').to_lowercase();
+            skipping = BOILERPLATE.contains(&title.as_str());
+        }
+        if !skipping {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Look a section up by number ("5", "Section 5.2", "§5") or by a
+/// case-insensitive title substring ("security"). Numbers win over titles.
+pub fn find_section<'a>(sections: &'a [Section], query: &str) -> Option<&'a Section> {
+    let query = query.trim();
+    let lower = query.to_lowercase();
+    let number = lower
+        .strip_prefix("section")
+        .or_else(|| lower.strip_prefix("appendix"))
+        .or_else(|| lower.strip_prefix('§'))
+        .unwrap_or(&lower)
+        .trim()
+        .trim_end_matches('.');
+
+    sections
+        .iter()
+        .find(|s| s.number.eq_ignore_ascii_case(number))
+        .or_else(|| sections.iter().find(|s| s.title.to_lowercase().contains(&lower)))
+}
+
+/// The lines of `section` within the text it was parsed from.
+pub fn section_text(text: &str, section: &Section) -> String {
+    let mut out = String::new();
+    for line in text.lines().skip(section.start).take(section.end - section.start) {
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "Status of This Memo
+
+   This is an Internet Standards Track document.
+
+Table of Contents
+
+   1.  Introduction ................................................  2
+     1.1.  Requirements Notation ...................................  2
+   2.  Security Considerations .....................................  3
+
+1.  Introduction
+
+   The protocol is introduced here.
+
+1.1.  Requirements Notation
+
+   The key words \"MUST\" and \"SHOULD\" are to be interpreted as described.
+
+2.  Security Considerations
+
+   Nothing is secure.
+A  Lone capital letter is not a heading.
+
+Appendix A.  Collected ABNF
+
+   rule = \"x\"
+";
+
+    #[test]
+    fn parses_numbered_headings_and_their_extent() {
+        let sections = parse_sections(TEXT);
+        let found: Vec<(&str, &str, usize, usize, usize)> =
+            sections.iter().map(|s| (s.number.as_str(), s.title.as_str(), s.depth, s.start, s.end)).collect();
+        assert_eq!(
+            found,
+            [
+                ("1", "Introduction", 1, 10, 18),
+                ("1.1", "Requirements Notation", 2, 14, 18),
+                ("2", "Security Considerations", 1, 18, 23),
+                ("A", "Collected ABNF", 1, 23, 26),
+            ]
+        );
+    }
+
+    #[test]
+    fn section_text_includes_subsections() {
+        let sections = parse_sections(TEXT);
+        let intro = section_text(TEXT, &sections[0]);
+        assert!(intro.starts_with("1.  Introduction\n"));
+        assert!(intro.contains("1.1.  Requirements Notation\n"));
+        assert!(!intro.contains("Security"));
+        assert_eq!(section_text(TEXT, &sections[3]), "Appendix A.  Collected ABNF\n\n   rule = \"x\"\n");
+    }
+
+    #[test]
+    fn finds_sections_by_number_or_title() {
+        let sections = parse_sections(TEXT);
+        let number = |query| find_section(&sections, query).map(|s| s.number.as_str());
+        assert_eq!(number("1.1"), Some("1.1"));
+        assert_eq!(number("Section 2"), Some("2"));
+        assert_eq!(number("§1.1."), Some("1.1"));
+        assert_eq!(number("appendix a"), Some("A"));
+        assert_eq!(number("security"), Some("2"));
+        assert_eq!(number("IANA"), None);
+    }
+
+    #[test]
+    fn strips_boilerplate_and_contents() {
+        let stripped = strip_boilerplate(TEXT);
+        assert!(stripped.starts_with("1.  Introduction\n"));
+        assert!(!stripped.contains("Internet Standards Track"));
+        assert!(!stripped.contains("....."));
+        assert!(stripped.contains("Nothing is secure."));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::index::RfcEntry;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+/// `rfcli.db` in the cache directory, only built with the `sqlite` feature.
+/// Holds the parsed index, so startup doesn't re-parse `rfc-index.txt`, and
+/// an FTS5 index of the cached RFC texts for `search`.
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open(path: &Path) -> rusqlite::Result<Store> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+             CREATE TABLE IF NOT EXISTS entries (
+                 number INTEGER PRIMARY KEY,
+                 title TEXT NOT NULL,
+                 authors TEXT NOT NULL,
+                 date TEXT NOT NULL,
+                 status TEXT NOT NULL,
+                 obsoletes TEXT NOT NULL,
+                 obsoleted_by TEXT NOT NULL,
+                 updates TEXT NOT NULL,
+                 updated_by TEXT NOT NULL
+             );
+             CREATE VIRTUAL TABLE IF NOT EXISTS bodies USING fts5(text, tokenize = 'porter unicode61');",
+        )?;
+        Ok(Store { conn })
+    }
+
+    /// The stored entries, as long as they were parsed from the index `fingerprint` identifies.
+    pub fn entries(&self, fingerprint: &str) -> rusqlite::Result<Option<Vec<RfcEntry>>> {
+        let stored: Option<String> = self
+            .conn
+            .query_row("SELECT value FROM meta WHERE key = 'index'", [], |row| row.get(0))
+            .optional()?;
+        if stored.as_deref() != Some(fingerprint) {
+            return Ok(None);
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT number, title, authors, date, status, obsoletes, obsoleted_by, updates, updated_by
+             FROM entries ORDER BY number",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(RfcEntry {
+                number: row.get(0)?,
+                title: row.get(1)?,
+                authors: from_json(row.get(2)?),
+                date: row.get(3)?,
+                status: row.get(4)?,
+                obsoletes: from_json(row.get(5)?),
+                obsoleted_by: from_json(row.get(6)?),
+                updates: from_json(row.get(7)?),
+                updated_by: from_json(row.get(8)?),
+            })
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>().map(Some)
+    }
+
+    /// Replace the stored entries with a fresh parse of the index `fingerprint` identifies.
+    pub fn save_entries(&mut self, entries: &[RfcEntry], fingerprint: &str) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM entries", [])?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR REPLACE INTO entries
+                 (number, title, authors, date, status, obsoletes, obsoleted_by, updates, updated_by)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            for entry in entries {
+                insert.execute(params![
+                    entry.number,
+                    entry.title,
+                    to_json(&entry.authors),
+                    entry.date,
+                    entry.status,
+                    to_json(&entry.obsoletes),
+                    to_json(&entry.obsoleted_by),
+                    to_json(&entry.updates),
+                    to_json(&entry.updated_by),
+                ])?;
+            }
+        }
+        tx.execute("INSERT OR REPLACE INTO meta (key, value) VALUES ('index', ?1)", params![fingerprint])?;
+        tx.commit()
+    }
+
+    /// RFC numbers whose text is in the full-text index.
+    pub fn indexed_bodies(&self) -> rusqlite::Result<Vec<u32>> {
+        let mut stmt = self.conn.prepare("SELECT rowid FROM bodies")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    /// Add the texts of `numbers` to the full-text index, in one transaction.
+    /// `load` returns None for texts that can't be read, those are skipped.
+    pub fn index_bodies(&mut self, numbers: &[u32], mut load: impl FnMut(u32) -> Option<String>) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        for &number in numbers {
+            let Some(text) = load(number) else { continue };
+            tx.execute("DELETE FROM bodies WHERE rowid = ?1", params![number])?;
+            tx.execute("INSERT INTO bodies (rowid, text) VALUES (?1, ?2)", params![number, text])?;
+        }
+        tx.commit()
+    }
+
+    /// RFCs whose text contains `term` as a phrase, best match first.
+    pub fn search(&self, term: &str) -> rusqlite::Result<Vec<u32>> {
+        // Quoted, the term is one FTS5 phrase rather than query syntax
+        let phrase = format!("\"{}\"", term.replace('"', "\"\""));
+        let mut stmt = self.conn.prepare("SELECT rowid FROM bodies WHERE bodies MATCH ?1 ORDER BY rank")?;
+        let rows = stmt.query_map(params![phrase], |row| row.get(0))?;
+        rows.collect()
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn from_json<T: DeserializeOwned + Default>(raw: String) -> T {
+    serde_json::from_str(&raw).unwrap_or_default()
+}
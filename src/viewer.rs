@@ -0,0 +1,400 @@
+use rfcli::sections::{self, Section};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::{cursor, queue, terminal};
+use std::io::{self, Write};
+
+/// Lines left above a heading or search hit that was jumped to.
+const JUMP_MARGIN: usize = 2;
+/// Width of the contents/references panel, including its border.
+const PANEL_WIDTH: usize = 38;
+const HELP: &str = "/ search  n/N next/prev  [ ] sections  t contents  r references  q quit";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Panel {
+    Contents,
+    References,
+}
+
+enum Mode {
+    Reading,
+    /// Typing a search, with what's been typed so far
+    Searching(String),
+    /// A side panel is open with this entry selected
+    Browsing(Panel, usize),
+}
+
+/// `read --tui`: a full-screen viewer for one cleaned RFC text, with search,
+/// section jumps and a list of the RFCs it references.
+struct Viewer<'a> {
+    title: &'a str,
+    lines: Vec<&'a str>,
+    sections: Vec<Section>,
+    references: &'a [u32],
+    /// First line on screen
+    top: usize,
+    /// Lowercase query of the last search
+    query: Option<String>,
+    /// One-off note for the status line ("Not found", ...)
+    note: Option<String>,
+    mode: Mode,
+    rows: usize,
+    cols: usize,
+}
+
+/// Show `text` until the reader quits (None) or picks one of `references`
+/// to read next. `start` is the line to open at, `query` a search to begin
+/// with, its matches highlighted and `n` going to the next one.
+pub fn run(title: &str, text: &str, references: &[u32], start: usize, query: Option<&str>) -> io::Result<Option<u32>> {
+    let (cols, rows) = terminal::size()?;
+    let mut viewer = Viewer {
+        title,
+        lines: text.lines().collect(),
+        sections: sections::parse_sections(text),
+        references,
+        top: 0,
+        query: query.filter(|q| !q.is_empty()).map(str::to_ascii_lowercase),
+        note: None,
+        mode: Mode::Reading,
+        rows: rows as usize,
+        cols: cols as usize,
+    };
+    viewer.jump_to(start);
+
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    queue!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+    let result = viewer.event_loop(&mut out);
+    // Hand the terminal back as we found it, even when drawing failed
+    let _ = queue!(out, cursor::Show, terminal::LeaveAlternateScreen);
+    let _ = out.flush();
+    let _ = terminal::disable_raw_mode();
+    result
+}
+
+impl Viewer<'_> {
+    fn event_loop(&mut self, out: &mut impl Write) -> io::Result<Option<u32>> {
+        loop {
+            self.draw(out)?;
+            match event::read()? {
+                Event::Key(key) if key.kind != KeyEventKind::Release => {
+                    if let Some(exit) = self.handle(key) {
+                        return Ok(exit);
+                    }
+                }
+                Event::Resize(cols, rows) => {
+                    (self.cols, self.rows) = (cols as usize, rows as usize);
+                    self.top = self.top.min(self.max_top());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Lines of text on screen, the last row is the status line.
+    fn page(&self) -> usize {
+        self.rows.saturating_sub(1).max(1)
+    }
+
+    fn max_top(&self) -> usize {
+        self.lines.len().saturating_sub(self.page())
+    }
+
+    fn scroll(&mut self, by: isize) {
+        self.top = self.top.saturating_add_signed(by).min(self.max_top());
+    }
+
+    fn jump_to(&mut self, line: usize) {
+        self.top = line.saturating_sub(JUMP_MARGIN).min(self.max_top());
+    }
+
+    /// Some(exit) once the viewer should close: Some(None) to quit, Some(Some(n)) to open RFC n.
+    fn handle(&mut self, key: KeyEvent) -> Option<Option<u32>> {
+        self.note = None;
+        let page = self.page() as isize;
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match &mut self.mode {
+            Mode::Searching(typed) => match key.code {
+                KeyCode::Esc => self.mode = Mode::Reading,
+                KeyCode::Backspace => {
+                    typed.pop();
+                }
+                KeyCode::Enter => {
+                    let typed = std::mem::take(typed);
+                    self.mode = Mode::Reading;
+                    if !typed.is_empty() {
+                        self.query = Some(typed.to_ascii_lowercase());
+                        self.search(true, true);
+                    }
+                }
+                KeyCode::Char(c) => typed.push(c),
+                _ => {}
+            },
+            Mode::Browsing(panel, selected) => {
+                let (panel, selected) = (*panel, *selected);
+                let last = self.panel_len(panel).saturating_sub(1);
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q' | 't' | 'r') => self.mode = Mode::Reading,
+                    KeyCode::Down | KeyCode::Char('j') => self.mode = Mode::Browsing(panel, (selected + 1).min(last)),
+                    KeyCode::Up | KeyCode::Char('k') => self.mode = Mode::Browsing(panel, selected.saturating_sub(1)),
+                    KeyCode::Enter => {
+                        self.mode = Mode::Reading;
+                        match panel {
+                            Panel::Contents => self.jump_to(self.sections[selected].start),
+                            Panel::References => return Some(self.references.get(selected).copied()),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Mode::Reading => match key.code {
+                KeyCode::Char('c') if ctrl => return Some(None),
+                KeyCode::Char('f') if ctrl => self.scroll(page),
+                KeyCode::Char('b') if ctrl => self.scroll(-page),
+                KeyCode::Char('q') | KeyCode::Esc => return Some(None),
+                KeyCode::Down | KeyCode::Enter | KeyCode::Char('j') => self.scroll(1),
+                KeyCode::Up | KeyCode::Char('k') => self.scroll(-1),
+                KeyCode::PageDown | KeyCode::Char(' ') => self.scroll(page),
+                KeyCode::PageUp | KeyCode::Char('b') => self.scroll(-page),
+                KeyCode::Char('d') => self.scroll(page / 2),
+                KeyCode::Char('u') => self.scroll(-page / 2),
+                KeyCode::Home | KeyCode::Char('g') => self.top = 0,
+                KeyCode::End | KeyCode::Char('G') => self.top = self.max_top(),
+                KeyCode::Char('/') => self.mode = Mode::Searching(String::new()),
+                KeyCode::Char('n') => self.search(true, false),
+                KeyCode::Char('N') => self.search(false, false),
+                KeyCode::Char(']') => self.next_section(true),
+                KeyCode::Char('[') => self.next_section(false),
+                KeyCode::Char('t') => self.open_panel(Panel::Contents),
+                KeyCode::Char('r') => self.open_panel(Panel::References),
+                _ => {}
+            },
+        }
+        None
+    }
+
+    /// Move to the next (or previous) line containing the query. A new
+    /// search also considers the line at the top of the screen.
+    fn search(&mut self, forward: bool, new: bool) {
+        let Some(query) = &self.query else {
+            self.note = Some("Nothing to search for, press / first".to_string());
+            return;
+        };
+        let current = self.top + JUMP_MARGIN.min(self.top);
+        let matches = |i: &usize| self.lines[*i].to_ascii_lowercase().contains(query.as_str());
+        let found = if forward {
+            let from = if new { current } else { current + 1 };
+            (from..self.lines.len()).find(matches)
+        } else {
+            (0..current).rev().find(matches)
+        };
+        match found {
+            Some(line) => self.jump_to(line),
+            None => self.note = Some(format!("Not found: {}", query)),
+        }
+    }
+
+    fn next_section(&mut self, forward: bool) {
+        let current = self.top + JUMP_MARGIN.min(self.top);
+        let found = if forward {
+            self.sections.iter().find(|s| s.start > current)
+        } else {
+            self.sections.iter().rev().find(|s| s.start < current)
+        };
+        match found {
+            Some(section) => self.jump_to(section.start),
+            None => self.note = Some(if forward { "Last section" } else { "First section" }.to_string()),
+        }
+    }
+
+    fn open_panel(&mut self, panel: Panel) {
+        if self.panel_len(panel) == 0 {
+            let what = if panel == Panel::Contents { "No numbered sections" } else { "No RFCs referenced" };
+            self.note = Some(what.to_string());
+            return;
+        }
+        // Contents open at the section being read
+        let current = self.top + JUMP_MARGIN.min(self.top);
+        let selected = match panel {
+            Panel::Contents => self.sections.iter().rposition(|s| s.start <= current).unwrap_or(0),
+            Panel::References => 0,
+        };
+        self.mode = Mode::Browsing(panel, selected);
+    }
+
+    fn panel_len(&self, panel: Panel) -> usize {
+        match panel {
+            Panel::Contents => self.sections.len(),
+            Panel::References => self.references.len(),
+        }
+    }
+
+    fn panel_entry(&self, panel: Panel, i: usize) -> String {
+        match panel {
+            Panel::Contents => {
+                let section = &self.sections[i];
+                format!("{}{} {}", "  ".repeat(section.depth - 1), section.number, section.title)
+            }
+            Panel::References => format!("RFC {}", self.references[i]),
+        }
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        let page = self.page();
+        let panel = match self.mode {
+            Mode::Browsing(panel, selected) => Some((panel, selected)),
+            _ => None,
+        };
+        let text_x = if panel.is_some() { PANEL_WIDTH } else { 0 };
+        let width = self.cols.saturating_sub(text_x);
+        // The panel scrolls just enough to keep the selection on screen
+        let panel_top = panel.map_or(0, |(_, selected)| selected.saturating_sub(page - 1));
+
+        for row in 0..page {
+            queue!(out, cursor::MoveTo(0, row as u16), terminal::Clear(terminal::ClearType::CurrentLine))?;
+            if let Some((panel, selected)) = panel {
+                let i = panel_top + row;
+                let entry = if i < self.panel_len(panel) { self.panel_entry(panel, i) } else { String::new() };
+                let entry = fit(&entry, PANEL_WIDTH - 2);
+                if i == selected {
+                    queue!(out, SetAttribute(Attribute::Reverse), Print(&entry), SetAttribute(Attribute::Reset))?;
+                } else {
+                    queue!(out, Print(&entry))?;
+                }
+                queue!(out, Print(" │"))?;
+            }
+            if let Some(line) = self.lines.get(self.top + row) {
+                let heading = self.sections.iter().any(|s| s.start == self.top + row);
+                self.draw_line(out, &fit(line, width), heading)?;
+            }
+        }
+
+        let status = match &self.mode {
+            Mode::Searching(typed) => format!("/{}", typed),
+            _ => {
+                let percent = if self.lines.len() <= page { 100 } else { (self.top + page) * 100 / self.lines.len() };
+                let left = format!(" {}  {}%", self.title, percent.min(100));
+                let right = self.note.as_deref().unwrap_or(HELP);
+                format!("{}  {}", left, right)
+            }
+        };
+        queue!(
+            out,
+            cursor::MoveTo(0, page as u16),
+            terminal::Clear(terminal::ClearType::CurrentLine),
+            SetAttribute(Attribute::Reverse),
+            Print(fit(&status, self.cols)),
+            SetAttribute(Attribute::Reset),
+        )?;
+        out.flush()
+    }
+
+    /// One line of text, headings in bold and search hits reversed.
+    fn draw_line(&self, out: &mut impl Write, line: &str, heading: bool) -> io::Result<()> {
+        if heading {
+            queue!(out, SetAttribute(Attribute::Bold))?;
+        }
+        let mut rest = line;
+        if let Some(query) = self.query.as_deref().filter(|q| !q.is_empty()) {
+            // ASCII lowercasing keeps byte offsets, so hits line up with the original
+            while let Some(at) = rest.to_ascii_lowercase().find(query) {
+                let end = at + query.len();
+                queue!(out, Print(&rest[..at]), SetAttribute(Attribute::Reverse), Print(&rest[at..end]), SetAttribute(Attribute::NoReverse))?;
+                rest = &rest[end..];
+            }
+        }
+        queue!(out, Print(rest), SetAttribute(Attribute::Reset))
+    }
+}
+
+/// `text` cut or padded to exactly `width` characters.
+fn fit(text: &str, width: usize) -> String {
+    let cut: String = text.chars().take(width).collect();
+    format!("{:<width$}", cut, width = width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn viewer<'a>(text: &'a str, references: &'a [u32]) -> Viewer<'a> {
+        Viewer {
+            title: "RFC 9999",
+            lines: text.lines().collect(),
+            sections: sections::parse_sections(text),
+            references,
+            top: 0,
+            query: None,
+            note: None,
+            mode: Mode::Reading,
+            rows: 6,
+            cols: 80,
+        }
+    }
+
+    fn press(viewer: &mut Viewer, keys: &str) -> Option<Option<u32>> {
+        keys.chars().find_map(|c| {
+            let code = if c == '\n' { KeyCode::Enter } else { KeyCode::Char(c) };
+            viewer.handle(KeyEvent::new(code, KeyModifiers::NONE))
+        })
+    }
+
+    /// Three sections of ten lines each, the word "needle" in the last two.
+    fn document() -> String {
+        let mut text = String::new();
+        for (n, title) in ["Introduction", "Protocol", "Security Considerations"].iter().enumerate() {
+            text.push_str(&format!("{}.  {}\n", n + 1, title));
+            for line in 0..9 {
+                let word = if n > 0 && line == 4 { "needle" } else { "hay" };
+                text.push_str(&format!("   {} {}\n", word, line));
+            }
+        }
+        text
+    }
+
+    #[test]
+    fn jumps_between_sections() {
+        let text = document();
+        let mut viewer = viewer(&text, &[]);
+        press(&mut viewer, "]");
+        assert_eq!(viewer.top, 10 - JUMP_MARGIN);
+        press(&mut viewer, "]");
+        assert_eq!(viewer.top, 20 - JUMP_MARGIN);
+        press(&mut viewer, "]");
+        assert_eq!(viewer.note.as_deref(), Some("Last section"));
+        press(&mut viewer, "[");
+        assert_eq!(viewer.top, 10 - JUMP_MARGIN);
+    }
+
+    #[test]
+    fn searches_forward_and_back() {
+        let text = document();
+        let mut viewer = viewer(&text, &[]);
+        press(&mut viewer, "/NEEDLE\n");
+        assert_eq!(viewer.top, 15 - JUMP_MARGIN);
+        press(&mut viewer, "n");
+        assert_eq!(viewer.top, 25 - JUMP_MARGIN);
+        press(&mut viewer, "n");
+        assert_eq!(viewer.note.as_deref(), Some("Not found: needle"));
+        press(&mut viewer, "N");
+        assert_eq!(viewer.top, 15 - JUMP_MARGIN);
+    }
+
+    #[test]
+    fn picks_a_reference_or_quits() {
+        let text = document();
+        let mut referencing = viewer(&text, &[2616, 9110]);
+        assert_eq!(press(&mut referencing, "rj\n"), Some(Some(9110)));
+        assert_eq!(press(&mut referencing, "q"), Some(None));
+
+        let mut unreferenced = viewer(&text, &[]);
+        assert_eq!(press(&mut unreferenced, "r"), None);
+        assert_eq!(unreferenced.note.as_deref(), Some("No RFCs referenced"));
+    }
+
+    #[test]
+    fn fits_text_to_a_width() {
+        assert_eq!(fit("Security", 4), "Secu");
+        assert_eq!(fit("§5", 4), "§5  ");
+    }
+}
@@ -0,0 +1,12 @@
+//! A proxy in the config that reqwest can't use fails every request, it
+//! doesn't quietly fall back to a client without it.
+
+#[tokio::test]
+async fn unusable_proxy_is_a_config_error() {
+    rfcli::config::init(rfcli::config::Config { proxy: Some("http://[::1".into()), ..Default::default() });
+    let error = rfcli::net::client().unwrap_err();
+    assert!(matches!(&error, rfcli::Error::Config(message) if message.contains("http://[::1")), "{}", error);
+
+    let error = rfcli::net::get("http://127.0.0.1:9/rfc2616.txt").await.unwrap_err();
+    assert!(matches!(error, rfcli::Error::Config(_)), "{}", error);
+}
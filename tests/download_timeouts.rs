@@ -0,0 +1,51 @@
+//! The timeout covers waiting for the server, not the whole download: a
+//! large RFC on a slow link takes longer than it and still arrives.
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::time::Duration;
+
+/// Serves one request, the headers after `wait` and the body in `chunks`
+/// pieces `gap` apart.
+fn serve(wait: Duration, chunks: usize, gap: Duration) -> String {
+    rfcli::config::init(rfcli::config::Config { timeout_secs: Some(1), retries: Some(0), ..Default::default() });
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/rfc2616.txt", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0u8; 4096];
+        let _ = stream.read(&mut request);
+        std::thread::sleep(wait);
+        let body = "   Some text of the RFC.\n".repeat(chunks);
+        let _ = write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", body.len());
+        for line in body.lines() {
+            std::thread::sleep(gap);
+            let _ = writeln!(stream, "{}", line);
+        }
+    });
+    url
+}
+
+#[tokio::test]
+async fn slow_bodies_are_not_cut_off() {
+    // 2.5 seconds in all, never more than a quarter second without data
+    let url = serve(Duration::ZERO, 10, Duration::from_millis(250));
+    let response = rfcli::net::get(&url).await.unwrap();
+    let body = rfcli::download_with_progress(response, "Fetching").await.unwrap();
+    assert_eq!(body.lines().count(), 10);
+}
+
+#[tokio::test]
+async fn stalled_bodies_time_out() {
+    let url = serve(Duration::ZERO, 2, Duration::from_secs(2));
+    let response = rfcli::net::get(&url).await.unwrap();
+    let error = rfcli::download_with_progress(response, "Fetching").await.unwrap_err();
+    assert!(matches!(&error, rfcli::Error::Http(message) if message.contains("stalled")), "{}", error);
+}
+
+#[tokio::test]
+async fn missing_answers_time_out() {
+    let url = serve(Duration::from_secs(2), 1, Duration::ZERO);
+    let error = rfcli::net::get(&url).await.unwrap_err();
+    assert!(matches!(&error, rfcli::Error::Http(message) if message.contains("did not answer within 1s")), "{}", error);
+}
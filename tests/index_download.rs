@@ -0,0 +1,67 @@
+//! The index download runs on the same runtime as everything else, a slow
+//! server must not hold up the other tasks while the body trickles in.
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+const ENTRY: &str = "0001 Host Software. S. Crocker. April 1969. (Format: TXT, HTML) (Status:
+     UNKNOWN) (DOI: 10.17487/RFC0001)
+
+";
+
+/// Answers one request with the index, in chunks 50ms apart.
+fn serve_slowly(listener: TcpListener, body: String) {
+    let (mut stream, _) = listener.accept().unwrap();
+    let mut request = [0u8; 4096];
+    let _ = stream.read(&mut request);
+    write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n", body.len()).unwrap();
+    for chunk in body.as_bytes().chunks(body.len().div_ceil(10)) {
+        std::thread::sleep(Duration::from_millis(50));
+        stream.write_all(chunk).unwrap();
+        stream.flush().unwrap();
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn index_download_does_not_block_the_runtime() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mirror = format!("http://{}", listener.local_addr().unwrap());
+    let cache = std::env::temp_dir().join(format!("rfcli-index-test-{}", std::process::id()));
+    rfcli::config::init(rfcli::config::Config {
+        cache_dir: Some(cache.clone()),
+        mirrors: vec![mirror],
+        retries: Some(0),
+        ..Default::default()
+    });
+    let body = ENTRY.repeat(200);
+    let server = std::thread::spawn({
+        let body = body.clone();
+        move || serve_slowly(listener, body)
+    });
+
+    // One thread for both, the ticker only gets a turn while the download waits
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let ticker = tokio::spawn({
+        let ticks = ticks.clone();
+        async move {
+            loop {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                ticks.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    });
+
+    let index = rfcli::load_index(false, false).await;
+    ticker.abort();
+    server.join().unwrap();
+
+    assert_eq!(index.unwrap(), body);
+    assert_eq!(std::fs::read_to_string(cache.join("rfc-index.txt")).unwrap(), body);
+    // About 50 in the half second the body takes, none if the download blocked
+    let ticks = ticks.load(Ordering::Relaxed);
+    assert!(ticks >= 20, "the ticker ran {} times during the download", ticks);
+    let _ = std::fs::remove_dir_all(&cache);
+}